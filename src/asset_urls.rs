@@ -0,0 +1,215 @@
+use crate::LocalResponse;
+
+/// Rewrites relative `src` and `<link href>` URLs in HTML responses so they
+/// resolve against a custom scheme, such as the one Tauri serves bundled
+/// assets from. Other `href`s, such as `<a>` navigation links, keep going
+/// through the HTMX bridge unless `href` is added to the attributes.
+///
+/// Only `text/html` responses are touched. URLs with a scheme (`https:`,
+/// `data:`, `mailto:`, ...), protocol-relative URLs (`//host`) and fragment or
+/// query-only references are left as they are. Other relative URLs are joined
+/// to `base`, so with a base of `asset://localhost`, `<img src="/logo.png">`
+/// becomes `<img src="asset://localhost/logo.png">`.
+#[derive(Clone, Debug)]
+pub struct AssetUrlRewriter {
+    pub base: String,
+    /// Attributes rewritten on every tag.
+    pub attributes: Vec<String>,
+    /// Also rewrite `href` on `<link>` tags, e.g. stylesheets and icons.
+    pub link_href: bool,
+}
+
+impl AssetUrlRewriter {
+    pub fn new(base: impl Into<String>) -> Self {
+        AssetUrlRewriter {
+            base: base.into().trim_end_matches('/').to_string(),
+            attributes: vec!["src".to_string()],
+            link_href: true,
+        }
+    }
+
+    /// Replaces the attributes rewritten on every tag, e.g. with `src` and
+    /// `href` to also send `<a>` links to the asset scheme.
+    pub fn with_attributes(mut self, attributes: Vec<String>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    pub fn rewrite(&self, response: LocalResponse) -> LocalResponse {
+        let is_html = response
+            .header("content-type")
+            .is_some_and(|value| value.trim().to_ascii_lowercase().starts_with("text/html"));
+        if !is_html {
+            return response;
+        }
+        let Some(html) = response.body_str() else {
+            return response;
+        };
+
+        let rewritten = self.rewrite_html(html);
+        LocalResponse {
+            body: rewritten.into_bytes(),
+            ..response
+        }
+    }
+
+    pub fn rewrite_html(&self, html: &str) -> String {
+        // ASCII lowercasing keeps byte offsets identical to `html`.
+        let lowercase = html.to_ascii_lowercase();
+        let mut output = String::with_capacity(html.len());
+        let mut copied = 0;
+        while let Some((value_start, quote)) = self.next_attribute(html, &lowercase, copied) {
+            let Some(value_len) = html[value_start..].find(quote) else {
+                break;
+            };
+            let value = &html[value_start..value_start + value_len];
+            output.push_str(&html[copied..value_start]);
+            if is_relative(value) {
+                output.push_str(&self.base);
+                if !value.starts_with('/') {
+                    output.push('/');
+                }
+            }
+            output.push_str(value);
+            copied = value_start + value_len;
+        }
+        output.push_str(&html[copied..]);
+        output
+    }
+
+    /// Finds the earliest quoted value of a configured attribute at or after
+    /// `from`, returning where the value starts and its quote character.
+    fn next_attribute(&self, html: &str, lowercase: &str, from: usize) -> Option<(usize, char)> {
+        let link_href = self.link_href
+            && !self
+                .attributes
+                .iter()
+                .any(|attribute| attribute.eq_ignore_ascii_case("href"));
+        self.attributes
+            .iter()
+            .map(|attribute| (attribute.to_ascii_lowercase(), false))
+            .chain(link_href.then(|| ("href".to_string(), true)))
+            .filter_map(|(attribute, link_only)| {
+                let needle = format!("{}=", attribute);
+                let mut offset = from;
+                while let Some(found) = lowercase[offset..].find(&needle) {
+                    let start = offset + found;
+                    let preceded_by_space = lowercase[..start]
+                        .chars()
+                        .next_back()
+                        .is_some_and(char::is_whitespace);
+                    let applies =
+                        preceded_by_space && (!link_only || in_link_tag(lowercase, start));
+                    let value_start = start + needle.len();
+                    let quote = html[value_start..].chars().next();
+                    if let (true, Some(quote @ ('"' | '\''))) = (applies, quote) {
+                        return Some((value_start + 1, quote));
+                    }
+                    offset = value_start;
+                }
+                None
+            })
+            .min_by_key(|(value_start, _)| *value_start)
+    }
+}
+
+/// Whether `position` is inside the opening tag of a `<link>` element.
+fn in_link_tag(lowercase: &str, position: usize) -> bool {
+    lowercase[..position].rfind('<').is_some_and(|open| {
+        let tag = &lowercase[open + 1..position];
+        !tag.contains('>')
+            && tag
+                .strip_prefix("link")
+                .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+    })
+}
+
+fn is_relative(url: &str) -> bool {
+    let url = url.trim();
+    if url.is_empty() || url.starts_with("//") || url.starts_with('#') || url.starts_with('?') {
+        return false;
+    }
+    !has_scheme(url)
+}
+
+/// Whether `url` starts with a scheme such as `https:` or `data:`. A colon
+/// later in the URL, e.g. in a query, does not count.
+pub(crate) fn has_scheme(url: &str) -> bool {
+    url.split_once(':').is_some_and(|(scheme, _)| {
+        scheme
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use crate::{Dispatcher, LocalRequest};
+    use axum::{response::Html, routing::get, Router};
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_relative_urls_are_rewritten_in_html() {
+        let mut router = Router::new().route(
+            "/",
+            get(|| async {
+                Html(concat!(
+                    r#"<link rel="stylesheet" href="css/app.css">"#,
+                    r#"<img src="/logo.png">"#,
+                    r#"<a href='docs/intro.html'>docs</a>"#,
+                    r#"<img src="data:image/png;base64,AAAA">"#,
+                    r#"<a href="mailto:me@example.com">mail</a>"#,
+                    r#"<a href="https://example.com/x">ext</a>"#,
+                    r##"<a href="#top">top</a>"##,
+                ))
+            }),
+        );
+        let dispatcher =
+            Dispatcher::new().with_asset_url_rewriter(AssetUrlRewriter::new("asset://localhost/"));
+        let request = LocalRequest {
+            uri: "/".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let response = dispatcher.send(request, &mut router).await;
+        let body = response.body_str().unwrap();
+        assert!(body.contains(r#"href="asset://localhost/css/app.css">"#));
+        assert!(body.contains(r#"<img src="asset://localhost/logo.png">"#));
+        assert!(body.contains(r#"<a href='docs/intro.html'>"#));
+        assert!(body.contains(r#"src="data:image/png;base64,AAAA""#));
+        assert!(body.contains(r#"href="mailto:me@example.com""#));
+        assert!(body.contains(r#"href="https://example.com/x""#));
+        assert!(body.contains(r##"href="#top""##));
+    }
+
+    #[test]
+    fn test_rewriting_every_href_is_opt_in() {
+        let html = r#"<a href="/todos">todos</a><link href="/app.css">"#;
+
+        let rewriter = AssetUrlRewriter::new("asset://localhost")
+            .with_attributes(vec!["src".to_string(), "href".to_string()]);
+        assert_eq!(
+            rewriter.rewrite_html(html),
+            r#"<a href="asset://localhost/todos">todos</a><link href="asset://localhost/app.css">"#
+        );
+    }
+
+    #[test]
+    fn test_non_html_responses_are_untouched() {
+        let response = LocalResponse {
+            body: br#"<img src="/logo.png">"#.to_vec(),
+            ..Default::default()
+        }
+        .with_header("content-type", "text/plain");
+
+        let rewritten = AssetUrlRewriter::new("asset://localhost").rewrite(response);
+        assert_eq!(rewritten.body_str(), Some(r#"<img src="/logo.png">"#));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::LocalResponse;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Identifies a body held in a [`BodyStore`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct BodyToken(pub String);
+
+/// A side table for large response bodies, so they don't have to travel through
+/// the IPC bridge inside a [`LocalResponse`].
+///
+/// A response offloaded with [`LocalResponse::offload_body`] is sent to the
+/// webview with an empty `body` and a `body_token`. The Tauri side then exposes a
+/// second command (or custom scheme handler) that calls [`BodyStore::take`]:
+///
+/// ```rust,ignore
+/// #[tauri::command]
+/// fn local_app_body(store: State<'_, BodyStore>, token: BodyToken) -> Option<Vec<u8>> {
+///     store.take(&token)
+/// }
+/// ```
+///
+/// Clones share the same table.
+#[derive(Clone, Debug, Default)]
+pub struct BodyStore {
+    bodies: Arc<Mutex<HashMap<BodyToken, Vec<u8>>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl BodyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, body: Vec<u8>) -> BodyToken {
+        let token = BodyToken(format!(
+            "body-{}",
+            self.next_id.fetch_add(1, Ordering::Relaxed)
+        ));
+        self.bodies.lock().unwrap().insert(token.clone(), body);
+        token
+    }
+
+    pub fn get(&self, token: &BodyToken) -> Option<Vec<u8>> {
+        self.bodies.lock().unwrap().get(token).cloned()
+    }
+
+    /// Removes and returns the body, so each body is only handed out once.
+    pub fn take(&self, token: &BodyToken) -> Option<Vec<u8>> {
+        self.bodies.lock().unwrap().remove(token)
+    }
+
+    pub fn len(&self) -> usize {
+        self.bodies.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl LocalResponse {
+    /// Moves the body into `store` when it is larger than `threshold` bytes,
+    /// leaving a `body_token` in its place.
+    pub fn offload_body(mut self, store: &BodyStore, threshold: usize) -> Self {
+        if self.body.len() > threshold {
+            let body = std::mem::take(&mut self.body);
+            self.body_token = Some(store.insert(body));
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_and_take_by_token() {
+        let store = BodyStore::new();
+        let token = store.insert(b"large body".to_vec());
+
+        assert_eq!(store.get(&token), Some(b"large body".to_vec()));
+        assert_eq!(store.take(&token), Some(b"large body".to_vec()));
+        assert_eq!(store.take(&token), None);
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_offload_body_above_threshold() {
+        let store = BodyStore::new();
+        let small = LocalResponse::sse("small").offload_body(&store, 1024);
+        assert!(small.body_token.is_none());
+
+        let large = LocalResponse::sse("x".repeat(2048)).offload_body(&store, 1024);
+        assert!(large.body.is_empty());
+        let token = large.body_token.clone().unwrap();
+        assert_eq!(store.take(&token).unwrap().len(), 2048);
+
+        let json = serde_json::to_value(&large).unwrap();
+        assert_eq!(json["body_token"], token.0);
+    }
+}
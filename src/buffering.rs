@@ -0,0 +1,143 @@
+use crate::{LocalRequest, LocalResponse, StreamingResponse};
+use axum::body::HttpBody;
+use axum::http::header;
+use axum::response::Response;
+use axum::Router;
+use tower_service::Service;
+
+/// Decides whether a response body is collected up front or left to be
+/// streamed.
+///
+/// HTML fragments must arrive whole for HTMX to swap them, while large
+/// downloads are better streamed. A response is streamed when its content
+/// type starts with one of `stream_content_types` and, if `stream_above` is
+/// set, its size is unknown or larger than that many bytes. The default
+/// streams nothing, matching [`LocalRequest::send_to_router`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BufferingPolicy {
+    pub stream_content_types: Vec<String>,
+    pub stream_above: Option<u64>,
+}
+
+/// A response collected or left streaming according to a [`BufferingPolicy`].
+pub enum RoutedResponse {
+    Buffered(LocalResponse),
+    Streaming(StreamingResponse),
+}
+
+impl BufferingPolicy {
+    pub fn should_stream(&self, response: &Response) -> bool {
+        let headers = response.headers();
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if !self
+            .stream_content_types
+            .iter()
+            .any(|prefix| content_type.starts_with(&prefix.to_ascii_lowercase()))
+        {
+            return false;
+        }
+        let size = headers
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .or_else(|| response.body().size_hint().exact());
+        match (self.stream_above, size) {
+            (Some(threshold), Some(size)) => size > threshold,
+            _ => true,
+        }
+    }
+
+    pub async fn apply(&self, response: Response) -> RoutedResponse {
+        if self.should_stream(&response) {
+            RoutedResponse::Streaming(StreamingResponse::from_response(response))
+        } else {
+            RoutedResponse::Buffered(LocalResponse::from_response(response).await)
+        }
+    }
+}
+
+impl LocalRequest {
+    /// Sends the request and collects or streams the body as `policy` decides.
+    pub async fn send_to_router_with_policy(
+        self,
+        router: &mut Router,
+        policy: &BufferingPolicy,
+    ) -> RoutedResponse {
+        let request = match self.to_axum_request() {
+            Ok(request) => request,
+            Err(error) => return RoutedResponse::Buffered(LocalResponse::request_error(error)),
+        };
+        match router.call(request).await {
+            Ok(response) => policy.apply(response).await,
+            Err(error) => RoutedResponse::Buffered(LocalResponse::internal_server_error(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header::CONTENT_TYPE;
+    use axum::{response::Html, routing::get};
+    use std::collections::HashMap;
+
+    fn request(uri: &str) -> LocalRequest {
+        LocalRequest {
+            uri: uri.to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_html_is_buffered_and_large_download_is_streamed() {
+        let mut router = Router::new()
+            .route("/fragment", get(|| async { Html("<li>item</li>") }))
+            .route(
+                "/download",
+                get(|| async {
+                    (
+                        [(CONTENT_TYPE, "application/octet-stream")],
+                        vec![0u8; 1 << 20],
+                    )
+                }),
+            )
+            .route(
+                "/icon",
+                get(|| async { ([(CONTENT_TYPE, "application/octet-stream")], vec![0u8; 16]) }),
+            );
+        let policy = BufferingPolicy {
+            stream_content_types: vec!["application/octet-stream".to_string()],
+            stream_above: Some(64 * 1024),
+        };
+
+        let response = request("/fragment")
+            .send_to_router_with_policy(&mut router, &policy)
+            .await;
+        assert!(
+            matches!(response, RoutedResponse::Buffered(response) if response.body_str() == Some("<li>item</li>"))
+        );
+
+        let response = request("/download")
+            .send_to_router_with_policy(&mut router, &policy)
+            .await;
+        let RoutedResponse::Streaming(mut stream) = response else {
+            panic!("expected a streamed download");
+        };
+        let mut received = 0;
+        while let Some(chunk) = stream.next_chunk().await {
+            received += chunk.len();
+        }
+        assert_eq!(received, 1 << 20);
+
+        let response = request("/icon")
+            .send_to_router_with_policy(&mut router, &policy)
+            .await;
+        assert!(matches!(response, RoutedResponse::Buffered(_)));
+    }
+}
@@ -0,0 +1,48 @@
+use crate::LocalResponse;
+use std::time::Duration;
+
+impl LocalResponse {
+    /// Sets `Cache-Control` from typed options.
+    ///
+    /// Directives are written as `no-store`, `private`, `max-age=N`, in that
+    /// order. With no options set the response is marked `no-cache`, so it is
+    /// revalidated on every use.
+    pub fn cache_control(self, max_age: Option<Duration>, no_store: bool, private: bool) -> Self {
+        let mut directives = Vec::new();
+        if no_store {
+            directives.push("no-store".to_string());
+        }
+        if private {
+            directives.push("private".to_string());
+        }
+        if let Some(max_age) = max_age {
+            directives.push(format!("max-age={}", max_age.as_secs()));
+        }
+        if directives.is_empty() {
+            directives.push("no-cache".to_string());
+        }
+        self.with_header("cache-control", directives.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache_control(max_age: Option<Duration>, no_store: bool, private: bool) -> String {
+        LocalResponse::default()
+            .cache_control(max_age, no_store, private)
+            .header("cache-control")
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_cache_control_values() {
+        let hour = Some(Duration::from_secs(3600));
+        assert_eq!(cache_control(hour, false, false), "max-age=3600");
+        assert_eq!(cache_control(None, true, false), "no-store");
+        assert_eq!(cache_control(hour, false, true), "private, max-age=3600");
+        assert_eq!(cache_control(None, false, false), "no-cache");
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{LocalRequest, LocalResponse};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Stops calling a route's handler after repeated server errors.
+///
+/// Each path is tracked separately. After `failure_threshold` consecutive `5xx`
+/// responses the circuit for that path opens and the
+/// [`Dispatcher`](crate::Dispatcher) answers `503` without calling the router
+/// until `cooldown` has passed. The next request after the cooldown is let
+/// through; a success closes the circuit and a failure opens it again.
+///
+/// Clones share the same state.
+#[derive(Clone, Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    routes: Arc<Mutex<HashMap<String, RouteState>>>,
+}
+
+#[derive(Debug, Default)]
+struct RouteState {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            failure_threshold,
+            cooldown,
+            routes: Default::default(),
+        }
+    }
+
+    /// Whether requests to `path` are currently being short-circuited.
+    pub fn is_open(&self, path: &str) -> bool {
+        let routes = self.routes.lock().unwrap();
+        routes
+            .get(path)
+            .and_then(|state| state.opened_at)
+            .is_some_and(|opened_at| opened_at.elapsed() < self.cooldown)
+    }
+
+    /// Returns the `503` to answer with if the circuit for the request's path is open.
+    pub fn check(&self, request: &LocalRequest) -> Option<LocalResponse> {
+        let path = request.path();
+        if !self.is_open(path) {
+            return None;
+        }
+
+        let mut headers = BTreeMap::new();
+        headers.insert(
+            "retry-after".to_string(),
+            self.cooldown.as_secs().max(1).to_string(),
+        );
+        Some(LocalResponse {
+            status_code: 503,
+            body: format!("Circuit open for {}", path).into(),
+            headers,
+            generated_by_crate: true,
+            ..Default::default()
+        })
+    }
+
+    /// Records the outcome of a request that reached the router.
+    pub fn record(&self, request: &LocalRequest, response: &LocalResponse) {
+        let mut routes = self.routes.lock().unwrap();
+        let state = routes.entry(request.path().to_string()).or_default();
+        if response.status_code >= 500 {
+            state.consecutive_failures += 1;
+            if state.consecutive_failures >= self.failure_threshold {
+                state.opened_at = Some(Instant::now());
+            }
+        } else {
+            state.consecutive_failures = 0;
+            state.opened_at = None;
+        }
+    }
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use crate::Dispatcher;
+    use axum::{http::StatusCode, routing::get, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn request(uri: &str) -> LocalRequest {
+        LocalRequest {
+            uri: uri.to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_breaker_opens_after_consecutive_failures() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handler_calls = calls.clone();
+        let mut router = Router::new()
+            .route(
+                "/failing",
+                get(move || {
+                    handler_calls.fetch_add(1, Ordering::SeqCst);
+                    async { StatusCode::INTERNAL_SERVER_ERROR }
+                }),
+            )
+            .route("/healthy", get(|| async { "ok" }));
+        let dispatcher =
+            Dispatcher::new().with_circuit_breaker(CircuitBreaker::new(2, Duration::from_secs(60)));
+
+        for _ in 0..2 {
+            let response = dispatcher.send(request("/failing"), &mut router).await;
+            assert_eq!(response.status_code, 500);
+        }
+
+        let response = dispatcher
+            .send(request("/failing?retry=1"), &mut router)
+            .await;
+        assert_eq!(response.status_code, 503);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let response = dispatcher.send(request("/healthy"), &mut router).await;
+        assert_eq!(response.status_code, 200);
+    }
+
+    #[test]
+    fn test_breaker_closes_after_cooldown_and_success() {
+        let breaker = CircuitBreaker::new(1, Duration::ZERO);
+        let request = request("/route");
+        breaker.record(&request, &LocalResponse::internal_server_error("boom"));
+        assert!(breaker.check(&request).is_none());
+
+        let mut ok = LocalResponse::internal_server_error("");
+        ok.status_code = 200;
+        breaker.record(&request, &ok);
+        assert!(!breaker.is_open("/route"));
+    }
+}
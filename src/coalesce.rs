@@ -0,0 +1,127 @@
+use crate::{LocalRequest, LocalResponse};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+/// Lets concurrent identical `GET` requests share one trip to the router.
+///
+/// Requests are identical when their method, URI and `HX-Request` header
+/// match; the header is included because HTMX and full-page requests for the
+/// same URI usually get different bodies. The first request runs; the others
+/// wait for its response. If the first request is cancelled, a waiting
+/// request runs on its own instead.
+#[derive(Clone, Debug, Default)]
+pub struct RequestCoalescer {
+    in_flight: Arc<Mutex<HashMap<String, watch::Receiver<Option<LocalResponse>>>>>,
+}
+
+impl RequestCoalescer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `send` for `request`, or waits for the response of an identical
+    /// request already in flight.
+    pub async fn run<F>(&self, request: &LocalRequest, send: F) -> LocalResponse
+    where
+        F: Future<Output = LocalResponse>,
+    {
+        if !request.method.eq_ignore_ascii_case("GET") {
+            return send.await;
+        }
+        let key = format!(
+            "{} {} {}",
+            request.method.to_ascii_uppercase(),
+            request.uri,
+            request.is_htmx()
+        );
+
+        let leader = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(&key) {
+                Some(receiver) => Err(receiver.clone()),
+                None => {
+                    let (sender, receiver) = watch::channel(None);
+                    in_flight.insert(key.clone(), receiver);
+                    Ok(sender)
+                }
+            }
+        };
+        let sender = match leader {
+            Ok(sender) => sender,
+            Err(mut receiver) => {
+                if let Ok(response) = receiver.wait_for(Option::is_some).await {
+                    if let Some(response) = response.clone() {
+                        return response;
+                    }
+                }
+                return send.await;
+            }
+        };
+
+        let _cleanup = Cleanup {
+            coalescer: self,
+            key: &key,
+        };
+        let response = send.await;
+        sender.send_replace(Some(response.clone()));
+        response
+    }
+}
+
+/// Removes the in-flight entry when the leading request finishes or is
+/// cancelled.
+struct Cleanup<'a> {
+    coalescer: &'a RequestCoalescer,
+    key: &'a str,
+}
+
+impl Drop for Cleanup<'_> {
+    fn drop(&mut self) {
+        self.coalescer.in_flight.lock().unwrap().remove(self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Dispatcher, LocalRequest};
+    use axum::{routing::get, Router};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_identical_gets_run_once() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handler_calls = calls.clone();
+        let router = Router::new().route(
+            "/report",
+            get(move || async move {
+                handler_calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                "expensive"
+            }),
+        );
+        let dispatcher = Dispatcher::new().with_request_coalescing();
+        let request = LocalRequest {
+            uri: "/report".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let (mut first_router, mut second_router) = (router.clone(), router);
+        let (first, second) = tokio::join!(
+            dispatcher.send(request.clone(), &mut first_router),
+            dispatcher.send(request.clone(), &mut second_router),
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first.body_str(), Some("expensive"));
+        assert_eq!(second.body_str(), Some("expensive"));
+
+        dispatcher.send(request, &mut first_router).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::LocalResponse;
+
+impl LocalResponse {
+    /// The filename from the `Content-Disposition` header, decoded.
+    ///
+    /// The RFC 5987 `filename*=charset'lang'percent-encoded` form is preferred
+    /// over a plain or quoted `filename=` when both are present.
+    pub fn content_disposition_filename(&self) -> Option<String> {
+        let header = self.header("content-disposition")?;
+        let mut plain = None;
+        for param in split_params(header).into_iter().skip(1) {
+            let Some((name, value)) = param.split_once('=') else {
+                continue;
+            };
+            match name.trim().to_ascii_lowercase().as_str() {
+                "filename*" => {
+                    if let Some(decoded) = decode_ext_value(value.trim()) {
+                        return Some(decoded);
+                    }
+                }
+                "filename" => plain = Some(unquote(value.trim())),
+                _ => {}
+            }
+        }
+        plain
+    }
+
+    /// Whether `Content-Disposition` marks the body as a download the JS
+    /// client should save rather than swap into the page.
+    pub fn is_attachment(&self) -> bool {
+        self.header("content-disposition").is_some_and(|header| {
+            split_params(header)[0]
+                .trim()
+                .eq_ignore_ascii_case("attachment")
+        })
+    }
+
+    /// Builds a `200` download response for `bytes` named `filename`.
+    /// Non-ASCII filenames are also sent in the RFC 5987 `filename*` form,
+    /// with an ASCII approximation in `filename` for older clients.
+    pub fn attachment(filename: &str, bytes: Vec<u8>, content_type: &str) -> Self {
+        let fallback: String = filename
+            .chars()
+            .map(|c| {
+                if c.is_ascii() && !c.is_ascii_control() {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let mut disposition = format!(
+            "attachment; filename=\"{}\"",
+            fallback.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        if fallback != filename {
+            disposition.push_str(&format!("; filename*=UTF-8''{}", percent_encode(filename)));
+        }
+        LocalResponse {
+            body: bytes,
+            ..Default::default()
+        }
+        .with_header("content-type", content_type)
+        .with_header("content-disposition", disposition)
+    }
+}
+
+/// Splits a header value on `;`, ignoring separators inside quoted strings.
+pub(crate) fn split_params(header: &str) -> Vec<&str> {
+    let mut params = Vec::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (index, c) in header.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                params.push(&header[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    params.push(&header[start..]);
+    params
+}
+
+pub(crate) fn unquote(value: &str) -> String {
+    match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(inner) => {
+            let mut unquoted = String::with_capacity(inner.len());
+            let mut chars = inner.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => unquoted.extend(chars.next()),
+                    c => unquoted.push(c),
+                }
+            }
+            unquoted
+        }
+        None => value.to_string(),
+    }
+}
+
+/// Decodes an RFC 5987 `ext-value`. Only UTF-8 and ISO-8859-1 are supported.
+fn decode_ext_value(value: &str) -> Option<String> {
+    let mut parts = value.splitn(3, '\'');
+    let charset = parts.next()?;
+    let _language = parts.next()?;
+    let bytes = percent_decode(parts.next()?)?;
+
+    if charset.eq_ignore_ascii_case("utf-8") {
+        String::from_utf8(bytes).ok()
+    } else if charset.eq_ignore_ascii_case("iso-8859-1") {
+        Some(bytes.into_iter().map(char::from).collect())
+    } else {
+        None
+    }
+}
+
+/// Percent-encodes everything but RFC 5987 `attr-char`s.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z'
+            | b'A'..=b'Z'
+            | b'0'..=b'9'
+            | b'!'
+            | b'#'
+            | b'$'
+            | b'&'
+            | b'+'
+            | b'-'
+            | b'.'
+            | b'^'
+            | b'_'
+            | b'`'
+            | b'|'
+            | b'~' => char::from(byte).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+pub(crate) fn percent_decode(value: &str) -> Option<Vec<u8>> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(index + 1..index + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response_with_disposition(value: &str) -> LocalResponse {
+        let mut response = LocalResponse::default();
+        response
+            .headers
+            .insert("content-disposition".to_string(), value.to_string());
+        response
+    }
+
+    #[test]
+    fn test_rfc_5987_filename_is_decoded() {
+        let response = response_with_disposition(
+            "attachment; filename=\"fallback.txt\"; filename*=UTF-8''na%C3%AFve%20r%C3%A9sum%C3%A9.txt",
+        );
+        assert_eq!(
+            response.content_disposition_filename().as_deref(),
+            Some("naïve résumé.txt")
+        );
+    }
+
+    #[test]
+    fn test_attachment_constructor() {
+        let response = LocalResponse::attachment("résumé.pdf", vec![1, 2, 3], "application/pdf");
+
+        assert!(response.is_attachment());
+        assert_eq!(
+            response.header("content-disposition"),
+            Some("attachment; filename=\"r_sum_.pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf")
+        );
+        assert_eq!(response.header("content-type"), Some("application/pdf"));
+        assert_eq!(
+            response.content_disposition_filename().as_deref(),
+            Some("résumé.pdf")
+        );
+        assert!(!response_with_disposition("inline; filename=\"a.png\"").is_attachment());
+    }
+
+    #[test]
+    fn test_quoted_filename() {
+        let response =
+            response_with_disposition("attachment; filename=\"report; \\\"final\\\".pdf\"");
+        assert_eq!(
+            response.content_disposition_filename().as_deref(),
+            Some("report; \"final\".pdf")
+        );
+        assert_eq!(
+            LocalResponse::default().content_disposition_filename(),
+            None
+        );
+    }
+}
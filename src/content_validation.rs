@@ -0,0 +1,107 @@
+use crate::LocalResponse;
+
+/// Whether response bodies are checked against their declared `Content-Type`
+/// before they are handed to the webview. Off by default.
+///
+/// JSON types (`application/json` and `+json` suffixes) must parse as JSON and
+/// `text/*` bodies must be valid UTF-8. Other types are not checked.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ContentValidation {
+    #[default]
+    Off,
+    /// Logs a warning and passes the response through unchanged.
+    Log,
+    /// Replaces the response with a `500` whose body describes the mismatch.
+    Reject,
+}
+
+impl ContentValidation {
+    pub fn apply(self, response: LocalResponse) -> LocalResponse {
+        if self == ContentValidation::Off {
+            return response;
+        }
+        let Some(diagnostic) = response.content_mismatch() else {
+            return response;
+        };
+        tracing::warn!(status = response.status_code, "{}", diagnostic);
+        match self {
+            ContentValidation::Reject => LocalResponse::internal_server_error(diagnostic),
+            _ => response,
+        }
+    }
+}
+
+impl LocalResponse {
+    /// Describes why the body doesn't match its declared `Content-Type`, if it
+    /// doesn't. See [`ContentValidation`] for the types that are checked.
+    pub fn content_mismatch(&self) -> Option<String> {
+        if self.body_token.is_some() {
+            return None;
+        }
+        let content_type = self.header("content-type")?;
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        if mime == "application/json" || mime.ends_with("+json") {
+            serde_json::from_slice::<serde::de::IgnoredAny>(&self.body)
+                .err()
+                .map(|error| format!("Response body is not valid {}: {}", mime, error))
+        } else if mime.starts_with("text/") {
+            std::str::from_utf8(&self.body)
+                .err()
+                .map(|error| format!("Response body is not valid {}: {}", mime, error))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use crate::{Dispatcher, LocalRequest};
+    use axum::{http::header, routing::get, Router};
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_invalid_json_is_rejected_when_enabled() {
+        let mut router = Router::new().route(
+            "/data",
+            get(|| async { ([(header::CONTENT_TYPE, "application/json")], "not json") }),
+        );
+        let request = LocalRequest {
+            uri: "/data".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let unchecked = Dispatcher::new().send(request.clone(), &mut router).await;
+        assert_eq!(unchecked.status_code, 200);
+
+        let response = Dispatcher::new()
+            .with_content_validation(ContentValidation::Reject)
+            .send(request, &mut router)
+            .await;
+        assert_eq!(response.status_code, 500);
+        assert!(response
+            .body_str()
+            .unwrap()
+            .contains("Response body is not valid application/json"));
+    }
+
+    #[test]
+    fn test_text_must_be_utf8() {
+        let response = LocalResponse {
+            body: vec![0xff, 0xfe],
+            ..Default::default()
+        }
+        .with_header("content-type", "text/html; charset=utf-8");
+        assert!(response.content_mismatch().is_some());
+        assert_eq!(ContentValidation::Log.apply(response).status_code, 200);
+    }
+}
@@ -0,0 +1,350 @@
+use crate::{LocalRequest, LocalResponse};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::Duration;
+
+/// The `SameSite` attribute of a cookie.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+/// A cookie with its attributes, rendered as a `Set-Cookie` value by its
+/// `Display` implementation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    pub max_age: Option<Duration>,
+    pub path: Option<String>,
+    pub domain: Option<String>,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: Option<SameSite>,
+}
+
+impl Cookie {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Cookie {
+            name: name.into(),
+            value: value.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn with_domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    pub fn with_secure(mut self) -> Self {
+        self.secure = true;
+        self
+    }
+
+    pub fn with_http_only(mut self) -> Self {
+        self.http_only = true;
+        self
+    }
+
+    pub fn with_same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+}
+
+impl fmt::Display for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)?;
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age.as_secs())?;
+        }
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={}", domain)?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, "; Path={}", path)?;
+        }
+        if self.secure {
+            f.write_str("; Secure")?;
+        }
+        if self.http_only {
+            f.write_str("; HttpOnly")?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={:?}", same_site)?;
+        }
+        Ok(())
+    }
+}
+
+/// The cookies the app currently holds, keyed by name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CookieJar {
+    cookies: BTreeMap<String, Cookie>,
+}
+
+impl CookieJar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `cookie`, replacing any cookie with the same name.
+    pub fn insert(&mut self, cookie: Cookie) {
+        self.cookies.insert(cookie.name.clone(), cookie);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Cookie> {
+        self.cookies.get(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Cookie> {
+        self.cookies.remove(name)
+    }
+
+    /// Removes every cookie, returning them so they can be expired with
+    /// [`LocalResponse::expire_cookies`].
+    pub fn clear(&mut self) -> Vec<Cookie> {
+        std::mem::take(&mut self.cookies).into_values().collect()
+    }
+
+    /// The cookies in name order.
+    pub fn iter(&self) -> impl Iterator<Item = &Cookie> {
+        self.cookies.values()
+    }
+}
+
+impl LocalResponse {
+    /// Adds a `Set-Cookie` header for `cookie`.
+    ///
+    /// `headers` holds one value per name, so several cookies are kept in one
+    /// `set-cookie` entry separated by newlines, the same way
+    /// [`LocalResponse::from_response`] keeps them.
+    pub fn set_cookie(mut self, cookie: Cookie) -> Self {
+        let cookie = cookie.to_string();
+        match self.headers.get_mut("set-cookie") {
+            Some(existing) => {
+                existing.push('\n');
+                existing.push_str(&cookie);
+            }
+            None => {
+                self.headers.insert("set-cookie".to_string(), cookie);
+            }
+        }
+        self
+    }
+
+    /// Adds a `Set-Cookie` header with `Max-Age=0` for each cookie, so the
+    /// webview deletes them, e.g. on logout. The path and domain are kept,
+    /// since a cookie is only replaced by one with the same name, path and
+    /// domain.
+    pub fn expire_cookies(self, cookies: impl IntoIterator<Item = Cookie>) -> Self {
+        cookies.into_iter().fold(self, |response, cookie| {
+            response.set_cookie(Cookie {
+                value: String::new(),
+                max_age: Some(Duration::ZERO),
+                ..cookie
+            })
+        })
+    }
+}
+
+/// Rewrites the attributes of outgoing `Set-Cookie` headers, e.g. to drop
+/// `Secure` and relax `SameSite=Strict`, which can keep the webview from
+/// storing or sending cookies for the app's custom scheme.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CookieNormalization {
+    strip_secure: bool,
+    same_site: Option<SameSite>,
+}
+
+impl CookieNormalization {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes the `Secure` attribute.
+    pub fn strip_secure(mut self) -> Self {
+        self.strip_secure = true;
+        self
+    }
+
+    /// Replaces any `SameSite` attribute with `same_site`, adding it to
+    /// cookies that had none.
+    pub fn with_same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    pub fn apply(&self, mut response: LocalResponse) -> LocalResponse {
+        if let Some(cookies) = response.headers.get_mut("set-cookie") {
+            *cookies = cookies
+                .split('\n')
+                .map(|cookie| self.normalize(cookie))
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+        response
+    }
+
+    fn normalize(&self, cookie: &str) -> String {
+        let mut parts: Vec<&str> = cookie
+            .split(';')
+            .map(str::trim)
+            .enumerate()
+            .filter(|(index, attribute)| {
+                let name = attribute.split('=').next().unwrap_or_default().trim();
+                *index == 0
+                    || !(self.strip_secure && name.eq_ignore_ascii_case("secure")
+                        || self.same_site.is_some() && name.eq_ignore_ascii_case("samesite"))
+            })
+            .map(|(_, attribute)| attribute)
+            .collect();
+        let same_site = self
+            .same_site
+            .map(|same_site| format!("SameSite={:?}", same_site));
+        parts.extend(same_site.as_deref());
+        parts.join("; ")
+    }
+}
+
+impl LocalRequest {
+    /// Parses the `Cookie` header into name/value pairs. Pairs without an `=`
+    /// are ignored.
+    pub fn cookies(&self) -> HashMap<String, String> {
+        self.header("cookie")
+            .map(|header| {
+                header
+                    .split(';')
+                    .filter_map(|pair| {
+                        let (name, value) = pair.split_once('=')?;
+                        let name = name.trim();
+                        if name.is_empty() {
+                            return None;
+                        }
+                        Some((name.to_string(), value.trim().to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The cookies from the `Cookie` header as [`Cookie`]s, in header order.
+    /// Requests only carry names and values, so no attributes are set.
+    pub fn structured_cookies(&self) -> Vec<Cookie> {
+        self.header("cookie")
+            .into_iter()
+            .flat_map(|header| header.split(';'))
+            .filter_map(|pair| {
+                let (name, value) = pair.split_once('=')?;
+                let name = name.trim();
+                (!name.is_empty()).then(|| Cookie::new(name, value.trim()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cookies_are_parsed() {
+        let mut headers = HashMap::new();
+        headers.insert("Cookie".to_string(), "a=1; b=two".to_string());
+        let request = LocalRequest {
+            uri: "/".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers,
+        };
+
+        let cookies = request.cookies();
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies.get("a").unwrap(), "1");
+        assert_eq!(cookies.get("b").unwrap(), "two");
+    }
+
+    #[test]
+    fn test_missing_cookie_header_is_empty() {
+        let request = LocalRequest {
+            uri: "/".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        assert!(request.cookies().is_empty());
+    }
+
+    #[test]
+    fn test_set_cookie_header() {
+        let response = LocalResponse::default()
+            .set_cookie(
+                Cookie::new("session", "abc123")
+                    .with_max_age(Duration::from_secs(3600))
+                    .with_path("/")
+                    .with_http_only(),
+            )
+            .set_cookie(Cookie::new("theme", "dark").with_same_site(SameSite::Lax));
+
+        assert_eq!(
+            response.header("set-cookie"),
+            Some("session=abc123; Max-Age=3600; Path=/; HttpOnly\ntheme=dark; SameSite=Lax")
+        );
+
+        let mut headers = HashMap::new();
+        headers.insert(
+            "Cookie".to_string(),
+            "session=abc123; theme=dark".to_string(),
+        );
+        let request = LocalRequest {
+            uri: "/".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers,
+        };
+        assert_eq!(
+            request.structured_cookies(),
+            vec![
+                Cookie::new("session", "abc123"),
+                Cookie::new("theme", "dark")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_removed_cookie_is_expired() {
+        let mut jar = CookieJar::new();
+        jar.insert(Cookie::new("session", "abc123").with_path("/"));
+        jar.insert(Cookie::new("theme", "dark"));
+
+        let removed = jar.remove("session").unwrap();
+        assert_eq!(
+            jar.iter()
+                .map(|cookie| cookie.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["theme"]
+        );
+        let response = LocalResponse::default().expire_cookies([removed]);
+        assert_eq!(
+            response.header("set-cookie"),
+            Some("session=; Max-Age=0; Path=/")
+        );
+
+        assert_eq!(jar.clear().len(), 1);
+        assert!(jar.get("theme").is_none());
+    }
+}
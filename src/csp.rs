@@ -0,0 +1,233 @@
+use axum::body::Body;
+use axum::extract::Request;
+use axum::http::header::{self, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use base64::Engine;
+
+/// A per-request nonce for a nonce-based `Content-Security-Policy`.
+///
+/// The [`csp_nonce`] middleware stores one in the request extensions, so
+/// handlers rendering inline scripts can read it with
+/// `Extension<CspNonce>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CspNonce(pub String);
+
+impl CspNonce {
+    /// Generates a fresh nonce from 16 bytes of OS randomness.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system's random number generator fails.
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; 16];
+        getrandom::getrandom(&mut bytes).expect("OS random number generator failed");
+        CspNonce(base64::engine::general_purpose::STANDARD.encode(bytes))
+    }
+
+    /// A `Content-Security-Policy` value allowing same-origin resources and
+    /// inline scripts and styles carrying this nonce.
+    pub fn header_value(&self) -> String {
+        format!(
+            "default-src 'self'; script-src 'self' 'nonce-{0}'; style-src 'self' 'nonce-{0}'",
+            self.0
+        )
+    }
+}
+
+/// Adds `nonce="..."` to every `<script>` and `<style>` tag that doesn't
+/// already have one.
+pub fn inject_nonce(html: &str, nonce: &str) -> String {
+    let lowercase = html.to_ascii_lowercase();
+    let mut output = String::with_capacity(html.len());
+    let mut copied = 0;
+    let mut search = 0;
+    while let Some(found) = lowercase[search..].find('<') {
+        let start = search + found;
+        search = start + 1;
+        let Some(tag) = ["script", "style"]
+            .into_iter()
+            .find(|tag| lowercase[start + 1..].starts_with(tag))
+        else {
+            continue;
+        };
+        let name_end = start + 1 + tag.len();
+        if !lowercase[name_end..].starts_with(|c: char| c.is_whitespace() || c == '>') {
+            continue;
+        }
+        let tag_end = lowercase[name_end..]
+            .find('>')
+            .map_or(lowercase.len(), |end| name_end + end);
+        if has_nonce_attribute(&lowercase[name_end..tag_end]) {
+            continue;
+        }
+        output.push_str(&html[copied..name_end]);
+        output.push_str(&format!(" nonce=\"{}\"", nonce));
+        copied = name_end;
+    }
+    output.push_str(&html[copied..]);
+    output
+}
+
+/// Whether a tag's attributes include `nonce` itself, not just an attribute
+/// ending in it such as `data-nonce`.
+fn has_nonce_attribute(attributes: &str) -> bool {
+    attributes.match_indices("nonce").any(|(start, name)| {
+        let rest = &attributes[start + name.len()..];
+        attributes[..start].ends_with(char::is_whitespace)
+            && (rest.is_empty() || rest.starts_with(|c: char| c == '=' || c.is_whitespace()))
+    })
+}
+
+/// Middleware that gives each request a [`CspNonce`], adds it to inline
+/// scripts and styles in HTML responses and adds the matching
+/// `Content-Security-Policy` header. A policy set by the handler is kept, and
+/// both apply.
+///
+/// ```rust
+/// use axum::{middleware, routing::get, Router};
+/// use tauri_axum_htmx::csp_nonce;
+///
+/// let router: Router = Router::new()
+///     .route("/", get(|| async { "hello" }))
+///     .layer(middleware::from_fn(csp_nonce));
+/// ```
+pub async fn csp_nonce(mut request: Request, next: Next) -> Response {
+    let nonce = CspNonce::generate();
+    request.extensions_mut().insert(nonce.clone());
+    let response = next.run(request).await;
+
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"));
+    let (mut parts, body) = response.into_parts();
+    if let Ok(value) = HeaderValue::from_str(&nonce.header_value()) {
+        parts.headers.append(header::CONTENT_SECURITY_POLICY, value);
+    }
+    if !is_html {
+        return Response::from_parts(parts, body);
+    }
+
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+    let body = match std::str::from_utf8(&bytes) {
+        Ok(html) => Body::from(inject_nonce(html, &nonce.0)),
+        Err(_) => Body::from(bytes),
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, body)
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use crate::LocalRequest;
+    use axum::response::{AppendHeaders, Html};
+    use axum::{middleware, routing::get, Router};
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_script_nonce_matches_csp_header() {
+        let mut router = Router::new()
+            .route(
+                "/fragment",
+                get(|| async { Html("<div>hi</div><script>init()</script>") }),
+            )
+            .layer(middleware::from_fn(csp_nonce));
+        let request = LocalRequest {
+            uri: "/fragment".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let response = request.send_to_router(&mut router).await;
+        let csp = response.header("content-security-policy").unwrap();
+        let nonce = csp
+            .split("'nonce-")
+            .nth(1)
+            .and_then(|rest| rest.split('\'').next())
+            .unwrap();
+        assert_eq!(
+            response.body_str(),
+            Some(format!("<div>hi</div><script nonce=\"{}\">init()</script>", nonce).as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_report_only_policy_survives_round_trip() {
+        const POLICY: &str = "default-src 'self'; script-src 'self' 'unsafe-inline' https://cdn.example.com; report-uri /csp-report";
+        let mut router = Router::new().route(
+            "/",
+            get(|| async {
+                (
+                    AppendHeaders([
+                        (header::CONTENT_SECURITY_POLICY_REPORT_ONLY, POLICY),
+                        (header::CONTENT_SECURITY_POLICY, "img-src 'self'"),
+                        (header::CONTENT_SECURITY_POLICY, "frame-src 'none'"),
+                    ]),
+                    "ok",
+                )
+            }),
+        );
+        let request = LocalRequest {
+            uri: "/".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let response = request.send_to_router(&mut router).await;
+        assert_eq!(
+            response.header("content-security-policy-report-only"),
+            Some(POLICY)
+        );
+        assert_eq!(
+            response.header("content-security-policy"),
+            Some("img-src 'self', frame-src 'none'")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handler_policy_is_kept() {
+        let mut router = Router::new()
+            .route(
+                "/",
+                get(|| async { ([(header::CONTENT_SECURITY_POLICY, "img-src 'self'")], "ok") }),
+            )
+            .layer(middleware::from_fn(csp_nonce));
+        let request = LocalRequest {
+            uri: "/".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let response = request.send_to_router(&mut router).await;
+        let csp = response.header("content-security-policy").unwrap();
+        assert!(csp.starts_with("img-src 'self', default-src 'self'; script-src 'self' 'nonce-"));
+    }
+
+    #[test]
+    fn test_existing_nonces_and_other_tags_are_kept() {
+        let html = r#"<STYLE>a{}</STYLE><script nonce="x"></script><scripts></scripts>"#;
+        assert_eq!(
+            inject_nonce(html, "n"),
+            r#"<STYLE nonce="n">a{}</STYLE><script nonce="x"></script><scripts></scripts>"#
+        );
+        assert_ne!(CspNonce::generate(), CspNonce::generate());
+    }
+
+    #[test]
+    fn test_data_nonce_is_not_a_nonce() {
+        let html = r#"<script data-nonce="x">a()</script><script nonce = "y">b()</script>"#;
+        assert_eq!(
+            inject_nonce(html, "n"),
+            r#"<script nonce="n" data-nonce="x">a()</script><script nonce = "y">b()</script>"#
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use crate::LocalResponse;
+use base64::Engine;
+
+impl LocalResponse {
+    /// Encodes the body as a base64 `data:` URL for embedding in `<img>` or
+    /// `<iframe>` elements. The media type comes from `Content-Type`, falling back
+    /// to `application/octet-stream`.
+    ///
+    /// Returns `None` if the body has been moved into a [`BodyStore`](crate::BodyStore).
+    pub fn to_data_url(&self) -> Option<String> {
+        if self.body_token.is_some() {
+            return None;
+        }
+        let content_type = self
+            .header("content-type")
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .unwrap_or("application/octet-stream");
+
+        Some(format!(
+            "data:{};base64,{}",
+            content_type.replace(' ', ""),
+            base64::engine::general_purpose::STANDARD.encode(&self.body)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_png_body_becomes_data_url() {
+        let mut response = LocalResponse {
+            body: vec![0x89, b'P', b'N', b'G'],
+            ..Default::default()
+        };
+        response
+            .headers
+            .insert("content-type".to_string(), "image/png".to_string());
+
+        assert_eq!(
+            response.to_data_url().as_deref(),
+            Some("data:image/png;base64,iVBORw==")
+        );
+    }
+
+    #[test]
+    fn test_missing_content_type_falls_back() {
+        let response = LocalResponse {
+            body: b"hi".to_vec(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            response.to_data_url().as_deref(),
+            Some("data:application/octet-stream;base64,aGk=")
+        );
+    }
+}
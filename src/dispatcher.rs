@@ -0,0 +1,745 @@
+use crate::{
+    AssetUrlRewriter, CircuitBreaker, ContentValidation, CookieNormalization, EtagCache,
+    HeaderProjection, LocalRequest, LocalResponse, Metrics, PreflightResponder, RequestCoalescer,
+    ResponseHeaderRules, RetryPolicy, StaticFileResolver, StatusHandlers, UriNormalization,
+};
+use axum::Router;
+use futures_util::FutureExt;
+use std::fmt;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Sends [`LocalRequest`]s to a router, applying optional behaviour around the
+/// call such as retries, timeouts, circuit breaking or answering `OPTIONS`
+/// preflights.
+///
+/// A default `Dispatcher` behaves exactly like [`LocalRequest::send_to_router`].
+#[derive(Clone, Debug, Default)]
+pub struct Dispatcher {
+    retry_policy: Option<RetryPolicy>,
+    preflight: Option<PreflightResponder>,
+    timeout: Option<Duration>,
+    circuit_breaker: Option<CircuitBreaker>,
+    default_headers: Vec<(String, String)>,
+    asset_url_rewriter: Option<AssetUrlRewriter>,
+    static_files: Option<StaticFileResolver>,
+    uri_normalization: Option<UriNormalization>,
+    content_validation: ContentValidation,
+    title_case_headers: bool,
+    error_response: Option<LocalResponse>,
+    panic_response: Option<LocalResponse>,
+    response_header_rules: Option<ResponseHeaderRules>,
+    cookie_normalization: Option<CookieNormalization>,
+    header_projection: Option<HeaderProjection>,
+    status_remapper: Option<StatusRemapper>,
+    status_handlers: Option<StatusHandlers>,
+    json_errors: bool,
+    etag_cache: Option<EtagCache>,
+    metrics: Option<Metrics>,
+    max_uri_length: Option<usize>,
+    validate_body_encoding: bool,
+    coalescer: Option<RequestCoalescer>,
+    reconcile_content_length: bool,
+    #[cfg(feature = "fault-injection")]
+    fault_injector: Option<crate::FaultInjector>,
+}
+
+type RemapStatus = dyn Fn(&LocalResponse) -> Option<u16> + Send + Sync;
+
+#[derive(Clone)]
+struct StatusRemapper(Arc<RemapStatus>);
+
+impl fmt::Debug for StatusRemapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StatusRemapper")
+    }
+}
+
+impl Dispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    pub fn with_preflight(mut self, responder: PreflightResponder) -> Self {
+        self.preflight = Some(responder);
+        self
+    }
+
+    /// Gives each attempt `timeout` to complete, answering `504` otherwise. See
+    /// [`LocalRequest::send_to_router_with_timeout`].
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// Adds `name: value` to requests that don't already carry a `name` header.
+    pub fn with_default_header(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.default_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Advertises `Accept-Encoding: gzip, deflate` on requests that don't set
+    /// it, so compression-aware handlers and layers behave as they would for a
+    /// browser.
+    pub fn with_accept_encoding(self) -> Self {
+        self.with_default_header("accept-encoding", "gzip, deflate")
+    }
+
+    /// Sends `Connection: keep-alive`, or `close` without `keep_alive`, on
+    /// requests that don't set it, for middleware that behaves differently
+    /// per connection mode. There is no real connection behind the bridge,
+    /// so the header only informs handlers.
+    pub fn with_connection(self, keep_alive: bool) -> Self {
+        let value = if keep_alive { "keep-alive" } else { "close" };
+        self.with_default_header("connection", value)
+    }
+
+    /// Sends the user's locale, e.g. `"en-GB, en;q=0.8"` from the OS, as
+    /// `Accept-Language` on requests that don't set it.
+    pub fn with_accept_language(self, languages: impl Into<String>) -> Self {
+        self.with_default_header("accept-language", languages)
+    }
+
+    /// Renames request headers to Title-Case before dispatching. See
+    /// [`LocalRequest::title_case_headers`].
+    pub fn with_title_case_headers(mut self) -> Self {
+        self.title_case_headers = true;
+        self
+    }
+
+    /// Rewrites relative asset URLs in HTML responses. See [`AssetUrlRewriter`].
+    pub fn with_asset_url_rewriter(mut self, rewriter: AssetUrlRewriter) -> Self {
+        self.asset_url_rewriter = Some(rewriter);
+        self
+    }
+
+    /// Answers requests for files under a static root without calling the
+    /// router. See [`StaticFileResolver`].
+    pub fn with_static_files(mut self, resolver: StaticFileResolver) -> Self {
+        self.static_files = Some(resolver);
+        self
+    }
+
+    /// Normalizes request paths before anything else looks at them. See
+    /// [`UriNormalization`].
+    pub fn with_uri_normalization(mut self, normalization: UriNormalization) -> Self {
+        self.uri_normalization = Some(normalization);
+        self
+    }
+
+    /// Checks response bodies against their declared content type. See
+    /// [`ContentValidation`].
+    pub fn with_content_validation(mut self, validation: ContentValidation) -> Self {
+        self.content_validation = validation;
+        self
+    }
+
+    /// Corrects a `Content-Length` that doesn't match the body. See
+    /// [`LocalResponse::reconcile_content_length`].
+    pub fn with_content_length_reconciliation(mut self) -> Self {
+        self.reconcile_content_length = true;
+        self
+    }
+
+    /// Answers with `response` instead of whatever `5xx` the router produced.
+    /// Panics are not errors in this sense; see [`Dispatcher::with_panic_response`].
+    pub fn with_error_response(mut self, response: LocalResponse) -> Self {
+        self.error_response = Some(response);
+        self
+    }
+
+    /// Catches handler panics and answers with `response`, so a panic can be
+    /// told apart from an ordinary error, e.g. by an `X-Panic` header. Without
+    /// this, a panic propagates to the caller.
+    pub fn with_panic_response(mut self, response: LocalResponse) -> Self {
+        self.panic_response = Some(response);
+        self
+    }
+
+    /// Adds headers to responses for matching paths. See
+    /// [`ResponseHeaderRules`].
+    pub fn with_response_header_rules(mut self, rules: ResponseHeaderRules) -> Self {
+        self.response_header_rules = Some(rules);
+        self
+    }
+
+    /// Rewrites `Set-Cookie` attributes so the webview keeps the cookies. See
+    /// [`CookieNormalization`].
+    pub fn with_cookie_normalization(mut self, normalization: CookieNormalization) -> Self {
+        self.cookie_normalization = Some(normalization);
+        self
+    }
+
+    /// Drops response headers outside the projection's allowlist. See
+    /// [`HeaderProjection`].
+    pub fn with_header_projection(mut self, projection: HeaderProjection) -> Self {
+        self.header_projection = Some(projection);
+        self
+    }
+
+    /// Lets `remap` inspect each response and replace its status, e.g. to turn
+    /// a legacy handler's `200` with an error body into a `422`. Returning
+    /// `None` keeps the status.
+    pub fn with_status_remapper(
+        mut self,
+        remap: impl Fn(&LocalResponse) -> Option<u16> + Send + Sync + 'static,
+    ) -> Self {
+        self.status_remapper = Some(StatusRemapper(Arc::new(remap)));
+        self
+    }
+
+    /// Fills empty error responses with HTML fragments. See
+    /// [`StatusHandlers`].
+    pub fn with_status_handlers(mut self, handlers: StatusHandlers) -> Self {
+        self.status_handlers = Some(handlers);
+        self
+    }
+
+    /// Wraps text error bodies as `{"error": "..."}` for requests whose
+    /// `Accept` header prefers JSON. See
+    /// [`LocalResponse::with_json_error_envelope`].
+    pub fn with_json_errors(mut self) -> Self {
+        self.json_errors = true;
+        self
+    }
+
+    /// Tags `200` answers to `GET`s with an `ETag` and answers a repeated
+    /// `GET` with a matching `If-None-Match` with `304`, without calling the
+    /// router. See [`EtagCache`].
+    pub fn with_etag_cache(mut self, cache: EtagCache) -> Self {
+        self.etag_cache = Some(cache);
+        self
+    }
+
+    /// Records the count, status and latency of every request in `metrics`.
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Answers `414` without calling the router when the URI is longer than
+    /// `max` bytes.
+    pub fn with_max_uri_length(mut self, max: usize) -> Self {
+        self.max_uri_length = Some(max);
+        self
+    }
+
+    /// Answers `400` to text bodies containing `U+FFFD`, for apps whose
+    /// clients never send one on purpose. See
+    /// [`LocalRequest::validate_body_encoding`].
+    pub fn with_body_encoding_validation(mut self) -> Self {
+        self.validate_body_encoding = true;
+        self
+    }
+
+    /// Shares one response between concurrent identical `GET`s. See
+    /// [`RequestCoalescer`].
+    pub fn with_request_coalescing(mut self) -> Self {
+        self.coalescer = Some(RequestCoalescer::new());
+        self
+    }
+
+    /// Injects latency or error responses for testing. See
+    /// [`FaultInjector`](crate::FaultInjector).
+    #[cfg(feature = "fault-injection")]
+    pub fn with_fault_injector(mut self, injector: crate::FaultInjector) -> Self {
+        self.fault_injector = Some(injector);
+        self
+    }
+
+    pub async fn send(&self, request: LocalRequest, router: &mut Router) -> LocalResponse {
+        let started = Instant::now();
+        let response = match &self.coalescer {
+            Some(coalescer) => {
+                let key = request.clone();
+                coalescer.run(&key, self.dispatch(request, router)).await
+            }
+            None => self.dispatch(request, router).await,
+        };
+        if let Some(metrics) = &self.metrics {
+            metrics.record(response.status_code, started.elapsed());
+        }
+        response
+    }
+
+    async fn dispatch(&self, mut request: LocalRequest, router: &mut Router) -> LocalResponse {
+        if let Some(max) = self.max_uri_length.filter(|max| request.uri.len() > *max) {
+            return LocalResponse {
+                status_code: 414,
+                body: format!("URI longer than {} bytes", max).into(),
+                generated_by_crate: true,
+                ..Default::default()
+            };
+        }
+        if self.validate_body_encoding {
+            if let Err(error) = request.validate_body_encoding() {
+                return LocalResponse {
+                    status_code: 400,
+                    body: error.to_string().into(),
+                    generated_by_crate: true,
+                    ..Default::default()
+                };
+            }
+        }
+        if let Some(normalization) = &self.uri_normalization {
+            request.uri = normalization.normalize(&request.uri);
+        }
+        for (name, value) in &self.default_headers {
+            if request.header(name).is_none() {
+                request.headers.insert(name.clone(), value.clone());
+            }
+        }
+        if self.title_case_headers {
+            request.title_case_headers();
+        }
+
+        #[cfg(feature = "fault-injection")]
+        if let Some(injector) = &self.fault_injector {
+            if let Some(response) = injector.inject(&request).await {
+                return self.post_process(&request, response);
+            }
+        }
+
+        if let Some(resolver) = &self.static_files {
+            if let Some(response) = resolver.resolve(&request).await {
+                return self.post_process(&request, response);
+            }
+        }
+
+        if let Some(not_modified) = self
+            .etag_cache
+            .as_ref()
+            .and_then(|cache| cache.not_modified(&request))
+        {
+            return not_modified;
+        }
+
+        if let Some(rejection) = self
+            .circuit_breaker
+            .as_ref()
+            .and_then(|breaker| breaker.check(&request))
+        {
+            return rejection;
+        }
+
+        let response = match &self.panic_response {
+            Some(panic_response) => AssertUnwindSafe(self.send_with_retries(&request, router))
+                .catch_unwind()
+                .await
+                .map(|response| self.replace_error(response))
+                .unwrap_or_else(|_| {
+                    tracing::error!(uri = %request.uri, "handler panicked");
+                    panic_response.clone()
+                }),
+            None => self.replace_error(self.send_with_retries(&request, router).await),
+        };
+        if let Some(breaker) = &self.circuit_breaker {
+            breaker.record(&request, &response);
+        }
+        let response = match &self.preflight {
+            Some(responder) => responder.respond(&request, response),
+            None => response,
+        };
+        let response = self.post_process(&request, response);
+        match &self.etag_cache {
+            Some(cache) => cache.record(&request, response),
+            None => response,
+        }
+    }
+
+    fn replace_error(&self, response: LocalResponse) -> LocalResponse {
+        match &self.error_response {
+            Some(error_response) if response.status_code >= 500 => error_response.clone(),
+            _ => response,
+        }
+    }
+
+    /// Applies the configured response transformations, in order.
+    fn post_process(&self, request: &LocalRequest, mut response: LocalResponse) -> LocalResponse {
+        if let Some(StatusRemapper(remap)) = &self.status_remapper {
+            if let Some(status_code) = remap(&response) {
+                response.status_code = status_code;
+            }
+        }
+        if let Some(handlers) = &self.status_handlers {
+            response = handlers.apply(request, response);
+        }
+        if self.json_errors && request.prefers_json() {
+            response = response.with_json_error_envelope();
+        }
+        response = self.content_validation.apply(response);
+        if let Some(rewriter) = &self.asset_url_rewriter {
+            response = rewriter.rewrite(response);
+        }
+        if let Some(rules) = &self.response_header_rules {
+            response = rules.apply(request, response);
+        }
+        if let Some(normalization) = &self.cookie_normalization {
+            response = normalization.apply(response);
+        }
+        if let Some(projection) = &self.header_projection {
+            response = projection.apply(response);
+        }
+        if self.reconcile_content_length && !request.method.eq_ignore_ascii_case("HEAD") {
+            response = response.reconcile_content_length();
+        }
+        response
+    }
+
+    async fn send_with_retries(
+        &self,
+        request: &LocalRequest,
+        router: &mut Router,
+    ) -> LocalResponse {
+        let mut attempt = 1;
+        loop {
+            let response = match self.timeout {
+                Some(timeout) => {
+                    request
+                        .clone()
+                        .send_to_router_with_timeout(router, timeout)
+                        .await
+                }
+                None => request.clone().send_to_router(router).await,
+            };
+            match &self.retry_policy {
+                Some(policy) if policy.should_retry(request, &response, attempt) => {
+                    tokio::time::sleep(policy.delay_for(attempt)).await;
+                    attempt += 1;
+                }
+                _ => return response,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::HeaderMap, routing::get};
+    use std::collections::HashMap;
+
+    fn echo_header_router(name: &'static str) -> Router {
+        Router::new().route(
+            "/",
+            get(move |headers: HeaderMap| async move {
+                headers
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or("<absent>")
+                    .to_string()
+            }),
+        )
+    }
+
+    fn request(headers: HashMap<String, String>) -> LocalRequest {
+        LocalRequest {
+            uri: "/".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_accept_encoding_is_opt_in() {
+        let mut router = echo_header_router("accept-encoding");
+
+        let response = Dispatcher::new()
+            .send(request(HashMap::new()), &mut router)
+            .await;
+        assert_eq!(response.body_str(), Some("<absent>"));
+
+        let response = Dispatcher::new()
+            .with_accept_encoding()
+            .send(request(HashMap::new()), &mut router)
+            .await;
+        assert_eq!(response.body_str(), Some("gzip, deflate"));
+    }
+
+    #[tokio::test]
+    async fn test_configured_connection_reaches_handler() {
+        let mut router = Router::new().route(
+            "/",
+            get(|headers: HeaderMap| async move {
+                let connection = headers[axum::http::header::CONNECTION]
+                    .to_str()
+                    .unwrap()
+                    .to_string();
+                ([(axum::http::header::CONNECTION, "close")], connection)
+            }),
+        );
+
+        let response = Dispatcher::new()
+            .with_connection(true)
+            .send(request(HashMap::new()), &mut router)
+            .await;
+        assert_eq!(response.body_str(), Some("keep-alive"));
+        assert_eq!(response.header("connection"), None);
+    }
+
+    #[tokio::test]
+    async fn test_default_header_does_not_override_request() {
+        let mut router = echo_header_router("accept-encoding");
+        let mut headers = HashMap::new();
+        headers.insert("Accept-Encoding".to_string(), "br".to_string());
+
+        let response = Dispatcher::new()
+            .with_accept_encoding()
+            .send(request(headers), &mut router)
+            .await;
+        assert_eq!(response.body_str(), Some("br"));
+    }
+
+    #[tokio::test]
+    async fn test_uri_normalization_reaches_route() {
+        use crate::TrailingSlash;
+
+        let mut router = Router::new().route("/items/list", get(|| async { "items" }));
+        let dispatcher = Dispatcher::new().with_uri_normalization(
+            UriNormalization::new()
+                .with_dot_segments_removed()
+                .with_trailing_slash(TrailingSlash::Strip),
+        );
+        let request = LocalRequest {
+            uri: "/items/./list/?page=1".to_string(),
+            ..request(HashMap::new())
+        };
+
+        let response = dispatcher.send(request, &mut router).await;
+        assert_eq!(response.body_str(), Some("items"));
+    }
+
+    #[tokio::test]
+    async fn test_panic_response_is_distinct_from_error_response() {
+        use axum::http::StatusCode;
+
+        async fn panicking() -> &'static str {
+            panic!("boom")
+        }
+
+        let mut router = Router::new().route("/panic", get(panicking)).route(
+            "/error",
+            get(|| async { (StatusCode::INTERNAL_SERVER_ERROR, "db down") }),
+        );
+        let dispatcher = Dispatcher::new()
+            .with_error_response(LocalResponse::internal_server_error("request failed"))
+            .with_panic_response(
+                LocalResponse::internal_server_error("handler panicked")
+                    .with_header("x-panic", "true"),
+            );
+
+        let panicked = LocalRequest {
+            uri: "/panic".to_string(),
+            ..request(HashMap::new())
+        };
+        let response = dispatcher.send(panicked, &mut router).await;
+        assert_eq!(response.status_code, 500);
+        assert_eq!(response.header("x-panic"), Some("true"));
+
+        let failed = LocalRequest {
+            uri: "/error".to_string(),
+            ..request(HashMap::new())
+        };
+        let response = dispatcher.send(failed, &mut router).await;
+        assert_eq!(response.status_code, 500);
+        assert_eq!(response.header("x-panic"), None);
+        assert_eq!(
+            response.body_str(),
+            Some("An error occured: request failed")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_accept_language_default() {
+        let mut router = echo_header_router("accept-language");
+        let dispatcher = Dispatcher::new().with_accept_language("fr-FR");
+
+        let response = dispatcher.send(request(HashMap::new()), &mut router).await;
+        assert_eq!(response.body_str(), Some("fr-FR"));
+
+        let mut headers = HashMap::new();
+        headers.insert("Accept-Language".to_string(), "de".to_string());
+        let request = request(headers);
+        assert_eq!(request.accept_language(), Some("de"));
+        let response = dispatcher.send(request, &mut router).await;
+        assert_eq!(response.body_str(), Some("de"));
+    }
+
+    #[tokio::test]
+    async fn test_status_remapper_turns_error_body_into_422() {
+        let mut router = Router::new().route(
+            "/",
+            get(|| async { axum::Json(serde_json::json!({ "error": "title is required" })) }),
+        );
+        let dispatcher = Dispatcher::new().with_status_remapper(|response| {
+            let body: serde_json::Value = serde_json::from_slice(&response.body).ok()?;
+            body.get("error").map(|_| 422)
+        });
+
+        let response = dispatcher.send(request(HashMap::new()), &mut router).await;
+        assert_eq!(response.status_code, 422);
+    }
+
+    #[tokio::test]
+    async fn test_over_long_uri_is_rejected_before_routing() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handler_calls = calls.clone();
+        let mut router = Router::new().route(
+            "/",
+            get(move || async move {
+                handler_calls.fetch_add(1, Ordering::SeqCst);
+                "ok"
+            }),
+        );
+        let request = LocalRequest {
+            uri: format!("/?q={}", "x".repeat(100)),
+            ..request(HashMap::new())
+        };
+
+        let response = Dispatcher::new()
+            .with_max_uri_length(64)
+            .send(request, &mut router)
+            .await;
+        assert_eq!(response.status_code, 414);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_body_encoding_validation_is_opt_in() {
+        let mut router =
+            Router::new().route("/", axum::routing::post(|body: String| async { body }));
+        let mut headers = HashMap::new();
+        headers.insert("Content-Type".to_string(), "text/plain".to_string());
+        let request = LocalRequest {
+            method: "POST".to_string(),
+            body: Some("caf\u{fffd}".to_string()),
+            ..request(headers)
+        };
+
+        let response = Dispatcher::new().send(request.clone(), &mut router).await;
+        assert_eq!(response.status_code, 200);
+
+        let response = Dispatcher::new()
+            .with_body_encoding_validation()
+            .send(request, &mut router)
+            .await;
+        assert_eq!(response.status_code, 400);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_content_length_is_corrected() {
+        use axum::http::header::CONTENT_LENGTH;
+
+        let mut router =
+            Router::new().route("/", get(|| async { ([(CONTENT_LENGTH, "100")], "short") }));
+
+        let response = Dispatcher::new()
+            .send(request(HashMap::new()), &mut router)
+            .await;
+        assert_eq!(response.content_length(), Some(100));
+
+        let response = Dispatcher::new()
+            .with_content_length_reconciliation()
+            .send(request(HashMap::new()), &mut router)
+            .await;
+        assert_eq!(response.content_length(), Some(5));
+        assert_eq!(response.body_str(), Some("short"));
+    }
+
+    #[tokio::test]
+    async fn test_json_error_envelope() {
+        use axum::http::StatusCode;
+
+        let mut router = Router::new().route(
+            "/",
+            get(|| async { (StatusCode::UNPROCESSABLE_ENTITY, "title is required") }),
+        );
+        let dispatcher = Dispatcher::new().with_json_errors();
+        let mut headers = HashMap::new();
+        headers.insert("Accept".to_string(), "application/json".to_string());
+
+        let response = dispatcher.send(request(headers), &mut router).await;
+        assert_eq!(response.status_code, 422);
+        assert_eq!(response.header("content-type"), Some("application/json"));
+        assert_eq!(
+            response.body_str(),
+            Some(r#"{"error":"title is required"}"#)
+        );
+
+        let response = dispatcher.send(request(HashMap::new()), &mut router).await;
+        assert_eq!(response.body_str(), Some("title is required"));
+    }
+
+    #[tokio::test]
+    async fn test_matching_etag_gets_304_without_handler() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handler_calls = calls.clone();
+        let mut router = Router::new().route(
+            "/",
+            get(move || async move {
+                handler_calls.fetch_add(1, Ordering::SeqCst);
+                "<ul><li>milk</li></ul>"
+            }),
+        );
+        let dispatcher = Dispatcher::new().with_etag_cache(EtagCache::new());
+
+        let response = dispatcher.send(request(HashMap::new()), &mut router).await;
+        assert_eq!(response.status_code, 200);
+        let etag = response.header("etag").unwrap().to_string();
+
+        let mut headers = HashMap::new();
+        headers.insert("If-None-Match".to_string(), etag.clone());
+        let response = dispatcher.send(request(headers), &mut router).await;
+        assert_eq!(response.status_code, 304);
+        assert_eq!(response.header("etag"), Some(etag.as_str()));
+        assert!(response.body.is_empty());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_set_cookie_attributes_are_normalized() {
+        use crate::SameSite;
+        use axum::response::AppendHeaders;
+
+        let mut router = Router::new().route(
+            "/",
+            get(|| async {
+                AppendHeaders([
+                    ("set-cookie", "session=abc; Path=/; Secure; SameSite=Strict"),
+                    ("set-cookie", "theme=dark; HttpOnly"),
+                ])
+            }),
+        );
+        let dispatcher = Dispatcher::new().with_cookie_normalization(
+            CookieNormalization::new()
+                .strip_secure()
+                .with_same_site(SameSite::Lax),
+        );
+
+        let response = dispatcher.send(request(HashMap::new()), &mut router).await;
+        assert_eq!(
+            response.header("set-cookie"),
+            Some("session=abc; Path=/; SameSite=Lax\ntheme=dark; HttpOnly; SameSite=Lax")
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{LocalRequest, LocalResponse};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+impl LocalResponse {
+    /// Sets a strong `ETag` derived from the body, unless one is already set.
+    ///
+    /// The tag is a 64-bit FNV-1a hash plus the body length. It is stable
+    /// across runs but not collision resistant, which is fine for cache
+    /// validation.
+    pub fn with_computed_etag(self) -> Self {
+        if self.header("etag").is_some() {
+            return self;
+        }
+        let etag = format!("\"{:016x}-{:x}\"", fnv1a(&self.body), self.body.len());
+        self.with_header("etag", etag)
+    }
+}
+
+/// Remembers the `ETag` of the last `200` answer to each `GET` URI, so a
+/// repeated request with a matching `If-None-Match` can be answered with `304`
+/// without running the handler. HTMX and full-page requests for the same URI
+/// are tracked apart. Plugged into the dispatcher with
+/// `Dispatcher::with_etag_cache`.
+///
+/// Any other method on a path forgets the tags for that path, since it may
+/// have changed what a `GET` would return. State changed some other way has
+/// to be reported with [`EtagCache::invalidate`].
+///
+/// Clones share the same entries.
+#[derive(Clone, Debug, Default)]
+pub struct EtagCache {
+    etags: Arc<Mutex<HashMap<(String, bool), String>>>,
+}
+
+impl EtagCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `304` to answer `request` with, if it is a `GET` whose
+    /// `If-None-Match` matches the cached tag.
+    pub fn not_modified(&self, request: &LocalRequest) -> Option<LocalResponse> {
+        if !request.method.eq_ignore_ascii_case("GET") {
+            return None;
+        }
+        let if_none_match = request.header("if-none-match")?;
+        let etag = self.etags.lock().unwrap().get(&key(request))?.clone();
+        let matches = if_none_match.split(',').map(str::trim).any(|candidate| {
+            candidate == "*" || candidate.trim_start_matches("W/") == etag.trim_start_matches("W/")
+        });
+        matches.then(|| {
+            LocalResponse {
+                status_code: 304,
+                ..Default::default()
+            }
+            .with_header("etag", etag)
+        })
+    }
+
+    /// Tags a successful `GET` response and remembers the tag, or forgets the
+    /// tags for the path of any other method.
+    pub fn record(&self, request: &LocalRequest, response: LocalResponse) -> LocalResponse {
+        if !request.method.eq_ignore_ascii_case("GET") {
+            self.invalidate(request.path());
+            return response;
+        }
+        if response.status_code != 200 {
+            return response;
+        }
+        let response = response.with_computed_etag();
+        if let Some(etag) = response.header("etag") {
+            self.etags
+                .lock()
+                .unwrap()
+                .insert(key(request), etag.to_string());
+        }
+        response
+    }
+
+    /// Forgets the tags for `path`, whatever their query string.
+    pub fn invalidate(&self, path: &str) {
+        self.etags.lock().unwrap().retain(|(uri, _), _| {
+            let uri_path = uri.find(['?', '#']).map_or(uri.as_str(), |end| &uri[..end]);
+            uri_path != path
+        });
+    }
+
+    pub fn clear(&self) {
+        self.etags.lock().unwrap().clear();
+    }
+}
+
+fn key(request: &LocalRequest) -> (String, bool) {
+    (request.uri.clone(), request.is_htmx())
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn etag_for(body: &str) -> String {
+        LocalResponse {
+            body: body.as_bytes().to_vec(),
+            ..Default::default()
+        }
+        .with_computed_etag()
+        .header("etag")
+        .unwrap()
+        .to_string()
+    }
+
+    #[test]
+    fn test_etag_follows_body() {
+        assert_eq!(etag_for("<p>hello</p>"), etag_for("<p>hello</p>"));
+        assert_ne!(etag_for("<p>hello</p>"), etag_for("<p>hullo</p>"));
+        assert!(etag_for("").starts_with('"'));
+    }
+
+    #[test]
+    fn test_existing_etag_is_kept() {
+        let response = LocalResponse::default()
+            .with_header("ETag", "\"v1\"")
+            .with_computed_etag();
+        assert_eq!(response.header("etag"), Some("\"v1\""));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{LocalRequest, LocalResponse, PathMatcher};
+use std::time::Duration;
+
+#[derive(Clone, Debug)]
+enum Fault {
+    Latency(Duration),
+    Status(u16),
+}
+
+/// Injects latency or error responses for matching paths, to exercise how
+/// the JS client copes with slow or failing requests.
+///
+/// Latency rules delay the request before it continues; status rules answer
+/// without calling the router. Rules are checked in the order they were added,
+/// and every matching latency rule applies before the first matching status.
+#[derive(Clone, Debug, Default)]
+pub struct FaultInjector {
+    rules: Vec<(PathMatcher, Fault)>,
+}
+
+impl FaultInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_latency(mut self, matcher: PathMatcher, latency: Duration) -> Self {
+        self.rules.push((matcher, Fault::Latency(latency)));
+        self
+    }
+
+    pub fn with_status(mut self, matcher: PathMatcher, status_code: u16) -> Self {
+        self.rules.push((matcher, Fault::Status(status_code)));
+        self
+    }
+
+    /// Applies the faults for `request`, returning the injected response if a
+    /// status rule matched.
+    pub async fn inject(&self, request: &LocalRequest) -> Option<LocalResponse> {
+        for (matcher, fault) in &self.rules {
+            if !matcher.matches(request) {
+                continue;
+            }
+            match fault {
+                Fault::Latency(latency) => tokio::time::sleep(*latency).await,
+                Fault::Status(status_code) => {
+                    return Some(
+                        LocalResponse {
+                            status_code: *status_code,
+                            body: format!("Injected fault for {}", request.path()).into(),
+                            ..Default::default()
+                        }
+                        .with_header("x-fault-injected", "true"),
+                    )
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dispatcher;
+    use axum::{routing::get, Router};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_injected_status_skips_handler() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handler_calls = calls.clone();
+        let mut router = Router::new().route(
+            "/flaky",
+            get(move || async move {
+                handler_calls.fetch_add(1, Ordering::SeqCst);
+                "fine"
+            }),
+        );
+        let dispatcher = Dispatcher::new().with_fault_injector(
+            FaultInjector::new().with_status(PathMatcher::exact("/flaky"), 500),
+        );
+        let request = LocalRequest {
+            uri: "/flaky".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let response = dispatcher.send(request, &mut router).await;
+        assert_eq!(response.status_code, 500);
+        assert_eq!(response.header("x-fault-injected"), Some("true"));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{Error, LocalRequest};
+use serde::de::DeserializeOwned;
+
+impl LocalRequest {
+    /// Deserializes an `application/x-www-form-urlencoded` body, like
+    /// `axum::extract::Form` but before routing. A missing body is treated as
+    /// an empty form.
+    pub fn form<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        let content_type = self.header("content-type").unwrap_or_default();
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        if !mime.eq_ignore_ascii_case("application/x-www-form-urlencoded") {
+            return Err(Error::FormParseError(format!(
+                "expected application/x-www-form-urlencoded, got {:?}",
+                content_type
+            )));
+        }
+        serde_urlencoded::from_str(self.body.as_deref().unwrap_or_default())
+            .map_err(|error| Error::FormParseError(error.to_string()))
+    }
+
+    /// Deserializes the URI's query string, like `axum::extract::Query` but
+    /// before routing. A missing query string is treated as empty.
+    pub fn query<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        let without_fragment = self.uri.split('#').next().unwrap_or_default();
+        let query = without_fragment
+            .split_once('?')
+            .map_or("", |(_, query)| query);
+        serde_urlencoded::from_str(query).map_err(|error| Error::QueryParseError(error.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Person {
+        name: String,
+        age: u32,
+    }
+
+    fn request(content_type: &str, body: &str) -> LocalRequest {
+        let mut headers = HashMap::new();
+        headers.insert("Content-Type".to_string(), content_type.to_string());
+        LocalRequest {
+            uri: "/people".to_string(),
+            method: "POST".to_string(),
+            body: Some(body.to_string()),
+            headers,
+        }
+    }
+
+    #[test]
+    fn test_form_body_is_deserialized() {
+        let request = request(
+            "application/x-www-form-urlencoded; charset=UTF-8",
+            "name=Jane&age=30",
+        );
+        let person: Person = request.form().unwrap();
+        assert_eq!(
+            person,
+            Person {
+                name: "Jane".to_string(),
+                age: 30
+            }
+        );
+    }
+
+    #[test]
+    fn test_query_is_deserialized() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Search {
+            q: String,
+            page: Option<u32>,
+        }
+
+        let mut request = request("text/plain", "");
+        request.uri = "/search?q=hi&page=2#results".to_string();
+        let search: Search = request.query().unwrap();
+        assert_eq!(
+            search,
+            Search {
+                q: "hi".to_string(),
+                page: Some(2)
+            }
+        );
+
+        request.uri = "/search".to_string();
+        let params: HashMap<String, String> = request.query().unwrap();
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn test_non_form_content_type_is_rejected() {
+        let request = request("application/json", r#"{"name":"Jane","age":30}"#);
+        assert!(matches!(
+            request.form::<Person>(),
+            Err(Error::FormParseError(_))
+        ));
+    }
+}
@@ -0,0 +1,92 @@
+//! Inflates gzip-compressed request bodies.
+//!
+//! `LocalRequest::body` is a string, so a compressed body has to cross the
+//! bridge base64-encoded. When a request carries `Content-Encoding: gzip`,
+//! [`LocalRequest::to_axum_request`](crate::LocalRequest::to_axum_request)
+//! decodes and inflates the body and drops the header, so handlers see the
+//! plain content.
+//!
+//! Inflation stops at [`MAX_INFLATED_BODY_SIZE`], so a small compressed body
+//! cannot expand without bound before any body limit applies. Larger bodies
+//! are rejected with `413 Payload Too Large`.
+
+use crate::Error;
+use base64::Engine;
+use flate2::read::GzDecoder;
+use std::io::Read;
+
+/// The largest body a gzip request may inflate to: 2 MiB, the same as axum's
+/// default body limit.
+pub const MAX_INFLATED_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+pub(crate) fn inflate_body(body: &str) -> Result<Vec<u8>, Error> {
+    let compressed = base64::engine::general_purpose::STANDARD
+        .decode(body.trim())
+        .map_err(|error| Error::RequestBodyDecode(error.to_string()))?;
+    let mut inflated = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .take(MAX_INFLATED_BODY_SIZE as u64 + 1)
+        .read_to_end(&mut inflated)
+        .map_err(|error| Error::RequestBodyDecode(error.to_string()))?;
+    if inflated.len() > MAX_INFLATED_BODY_SIZE {
+        return Err(Error::RequestBodyTooLarge(MAX_INFLATED_BODY_SIZE));
+    }
+    Ok(inflated)
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use crate::LocalRequest;
+    use axum::{routing::post, Router};
+    use base64::Engine;
+    use flate2::{write::GzEncoder, Compression};
+    use std::collections::HashMap;
+    use std::io::Write;
+
+    #[tokio::test]
+    async fn test_gzip_body_is_inflated() {
+        let mut router = Router::new().route(
+            "/upload",
+            post(|headers: axum::http::HeaderMap, body: String| async move {
+                format!("{:?} {}", headers.get("content-encoding"), body)
+            }),
+        );
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello from the webview").unwrap();
+        let compressed = encoder.finish().unwrap();
+        let mut headers = HashMap::new();
+        headers.insert("Content-Encoding".to_string(), "gzip".to_string());
+        headers.insert("Content-Type".to_string(), "text/plain".to_string());
+        let request = LocalRequest {
+            uri: "/upload".to_string(),
+            method: "POST".to_string(),
+            body: Some(base64::engine::general_purpose::STANDARD.encode(compressed)),
+            headers,
+        };
+
+        let response = request.send_to_router(&mut router).await;
+        assert_eq!(response.body_str(), Some("None hello from the webview"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_inflated_body_is_rejected() {
+        let mut router = Router::new().route("/upload", post(|| async { "reached" }));
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder
+            .write_all(&vec![0; super::MAX_INFLATED_BODY_SIZE + 1])
+            .unwrap();
+        let compressed = encoder.finish().unwrap();
+        let mut headers = HashMap::new();
+        headers.insert("Content-Encoding".to_string(), "gzip".to_string());
+        let request = LocalRequest {
+            uri: "/upload".to_string(),
+            method: "POST".to_string(),
+            body: Some(base64::engine::general_purpose::STANDARD.encode(compressed)),
+            headers,
+        };
+
+        let response = request.send_to_router(&mut router).await;
+        assert_eq!(response.status_code, 413);
+        assert_ne!(response.body_str(), Some("reached"));
+    }
+}
@@ -0,0 +1,73 @@
+//! Serializes `LocalResponse::headers` as an array of `[name, value]` pairs
+//! instead of an object, so the JS client sees every value of a repeated
+//! header. With this feature, [`LocalResponse::from_response`] keeps repeated
+//! headers newline-separated in the map, and each line becomes its own pair.
+//!
+//! [`LocalResponse::from_response`]: crate::LocalResponse::from_response
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+pub(crate) fn serialize<S: Serializer>(
+    headers: &BTreeMap<String, String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    headers
+        .iter()
+        .flat_map(|(name, value)| value.split('\n').map(move |value| [name, value]))
+        .collect::<Vec<_>>()
+        .serialize(serializer)
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, String>, D::Error> {
+    let pairs = Vec::<[String; 2]>::deserialize(deserializer)?;
+    let mut headers: BTreeMap<String, String> = BTreeMap::new();
+    for [name, value] in pairs {
+        match headers.get_mut(&name) {
+            Some(existing) => {
+                existing.push('\n');
+                existing.push_str(&value);
+            }
+            None => {
+                headers.insert(name, value);
+            }
+        }
+    }
+    Ok(headers)
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use crate::LocalResponse;
+    use axum::body::Body;
+    use axum::http::response::Builder;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_duplicate_headers_are_separate_pairs() {
+        let response = Builder::new()
+            .header("vary", "Accept")
+            .header("vary", "HX-Request")
+            .header("set-cookie", "a=1")
+            .header("set-cookie", "b=2")
+            .body(Body::from("ok"))
+            .unwrap();
+
+        let response = LocalResponse::from_response(response).await;
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(
+            json["headers"],
+            json!([
+                ["set-cookie", "a=1"],
+                ["set-cookie", "b=2"],
+                ["vary", "Accept"],
+                ["vary", "HX-Request"],
+            ])
+        );
+
+        let round_trip: LocalResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(round_trip.headers, response.headers);
+    }
+}
@@ -0,0 +1,68 @@
+use crate::LocalResponse;
+
+/// Keeps only the response headers the webview needs, to shrink the IPC
+/// payload. Headers such as `date`, `server` or `connection` mean nothing to
+/// HTMX and are dropped.
+///
+/// Entries are matched case-insensitively; an entry ending in `*` matches any
+/// header with that prefix. The default allowlist is `content-type`, `hx-*`,
+/// `set-cookie` and `location`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeaderProjection {
+    pub allowlist: Vec<String>,
+}
+
+impl Default for HeaderProjection {
+    fn default() -> Self {
+        HeaderProjection::new(["content-type", "hx-*", "set-cookie", "location"])
+    }
+}
+
+impl HeaderProjection {
+    pub fn new(allowlist: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        HeaderProjection {
+            allowlist: allowlist
+                .into_iter()
+                .map(|name| name.into().to_ascii_lowercase())
+                .collect(),
+        }
+    }
+
+    pub fn allows(&self, name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        self.allowlist
+            .iter()
+            .any(|entry| match entry.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == *entry,
+            })
+    }
+
+    pub fn apply(&self, mut response: LocalResponse) -> LocalResponse {
+        response.headers.retain(|name, _| self.allows(name));
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_allowlisted_headers_are_serialized() {
+        let response = LocalResponse::default()
+            .with_header("content-type", "text/html")
+            .with_header("hx-trigger", "saved")
+            .with_header("date", "Thu, 15 Oct 2026 10:00:00 GMT")
+            .with_header("server", "axum")
+            .with_header("connection", "keep-alive");
+
+        let projected = HeaderProjection::default().apply(response);
+        let names: Vec<&str> = projected.headers.keys().map(String::as_str).collect();
+        assert_eq!(names, ["content-type", "hx-trigger"]);
+        let json = serde_json::to_string(&projected).unwrap();
+        for dropped in ["date", "server", "connection"] {
+            assert!(!json.contains(dropped));
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{LocalRequest, LocalResponse};
+use std::collections::BTreeMap;
+
+impl LocalRequest {
+    /// Whether the request was issued by HTMX, i.e. carries `HX-Request: true`.
+    pub fn is_htmx(&self) -> bool {
+        self.header("hx-request")
+            .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+    }
+
+    /// Whether HTMX is restoring a page missing from its history cache, i.e.
+    /// the request carries `HX-History-Restore-Request: true`. Such requests
+    /// also carry `HX-Request` but need the full page:
+    ///
+    /// ```rust
+    /// # use tauri_axum_htmx::{LocalRequest, LocalResponse};
+    /// # fn render(request: &LocalRequest, full: String, fragment: String) -> LocalResponse {
+    /// LocalResponse::full_or_fragment(
+    ///     request.is_htmx() && !request.is_history_restore(),
+    ///     full,
+    ///     fragment,
+    /// )
+    /// # }
+    /// ```
+    pub fn is_history_restore(&self) -> bool {
+        self.header("hx-history-restore-request")
+            .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+    }
+}
+
+impl LocalResponse {
+    /// Builds a `200` HTML response containing `fragment` for HTMX requests and
+    /// `full` otherwise, e.g. for pages reached through `hx-boost`.
+    pub fn full_or_fragment(is_htmx: bool, full: String, fragment: String) -> Self {
+        let mut headers = BTreeMap::new();
+        headers.insert(
+            "content-type".to_string(),
+            "text/html; charset=utf-8".to_string(),
+        );
+        headers.insert("vary".to_string(), "HX-Request".to_string());
+
+        LocalResponse {
+            status_code: 200,
+            body: if is_htmx { fragment } else { full }.into_bytes(),
+            headers,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn request(headers: HashMap<String, String>) -> LocalRequest {
+        LocalRequest {
+            uri: "/page".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers,
+        }
+    }
+
+    #[test]
+    fn test_fragment_is_chosen_for_htmx_requests() {
+        let mut headers = HashMap::new();
+        headers.insert("HX-Request".to_string(), "true".to_string());
+        let request = request(headers);
+
+        let response = LocalResponse::full_or_fragment(
+            request.is_htmx(),
+            "<html>page</html>".to_string(),
+            "<div>fragment</div>".to_string(),
+        );
+        assert_eq!(response.body_str(), Some("<div>fragment</div>"));
+    }
+
+    #[test]
+    fn test_history_restore_request() {
+        let mut headers = HashMap::new();
+        headers.insert("HX-Request".to_string(), "true".to_string());
+        headers.insert("HX-History-Restore-Request".to_string(), "true".to_string());
+        let request = request(headers);
+
+        assert!(request.is_history_restore());
+        let response = LocalResponse::full_or_fragment(
+            request.is_htmx() && !request.is_history_restore(),
+            "<html>page</html>".to_string(),
+            "<div>fragment</div>".to_string(),
+        );
+        assert_eq!(response.body_str(), Some("<html>page</html>"));
+    }
+
+    #[test]
+    fn test_full_page_is_chosen_otherwise() {
+        let request = request(HashMap::new());
+
+        let response = LocalResponse::full_or_fragment(
+            request.is_htmx(),
+            "<html>page</html>".to_string(),
+            "<div>fragment</div>".to_string(),
+        );
+        assert!(!request.is_htmx());
+        assert_eq!(response.body_str(), Some("<html>page</html>"));
+        assert_eq!(
+            response.header("content-type"),
+            Some("text/html; charset=utf-8")
+        );
+    }
+}
@@ -56,15 +56,135 @@
 //! }
 //! ```
 
+#[cfg(feature = "runtime")]
+use axum::body::HttpBody;
 use axum::http::{self};
+#[cfg(feature = "runtime")]
 use axum::response::Response;
+#[cfg(feature = "runtime")]
 use axum::Router;
 use axum::{body::Body, http::Request};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
 use thiserror::Error;
+#[cfg(feature = "runtime")]
 use tower_service::Service;
+#[cfg(feature = "runtime")]
+use tracing::Instrument;
+
+mod asset_urls;
+mod body_store;
+#[cfg(feature = "runtime")]
+mod buffering;
+mod cache_control;
+mod circuit_breaker;
+#[cfg(feature = "runtime")]
+mod coalesce;
+mod content_disposition;
+mod content_validation;
+mod cookie;
+mod csp;
+mod data_url;
+#[cfg(feature = "runtime")]
+mod dispatcher;
+mod etag;
+#[cfg(feature = "fault-injection")]
+mod fault_injection;
+mod form;
+#[cfg(feature = "gzip")]
+mod gzip;
+#[cfg(feature = "header-pairs")]
+mod header_pairs;
+mod header_projection;
+mod htmx;
+#[cfg(feature = "runtime")]
+mod local_app;
+#[cfg(feature = "runtime")]
+mod local_server;
+mod metrics;
+mod mixed_replace;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+mod multipart;
+mod negotiate;
+mod path_matcher;
+mod preflight;
+mod problem;
+#[cfg(feature = "reqwest")]
+mod proxy;
+mod range;
+mod raw_bytes;
+#[cfg(feature = "runtime")]
+mod redirect;
+mod render;
+#[cfg(feature = "runtime")]
+mod replay;
+mod request_id;
+mod response_builder;
+mod response_header_rules;
+mod retry;
+mod route_table;
+mod sse;
+#[cfg(feature = "runtime")]
+mod sse_hub;
+#[cfg(feature = "runtime")]
+mod sse_stream;
+#[cfg(feature = "runtime")]
+mod static_files;
+mod status_handlers;
+mod streaming;
+#[cfg(feature = "runtime")]
+mod telemetry;
+mod timeout;
+mod uri_normalization;
+
+pub use asset_urls::AssetUrlRewriter;
+pub use body_store::{BodyStore, BodyToken};
+#[cfg(feature = "runtime")]
+pub use buffering::{BufferingPolicy, RoutedResponse};
+pub use circuit_breaker::CircuitBreaker;
+#[cfg(feature = "runtime")]
+pub use coalesce::RequestCoalescer;
+pub use content_validation::ContentValidation;
+pub use cookie::{Cookie, CookieJar, CookieNormalization, SameSite};
+pub use csp::{csp_nonce, inject_nonce, CspNonce};
+#[cfg(feature = "runtime")]
+pub use dispatcher::Dispatcher;
+pub use etag::EtagCache;
+#[cfg(feature = "fault-injection")]
+pub use fault_injection::FaultInjector;
+#[cfg(feature = "gzip")]
+pub use gzip::MAX_INFLATED_BODY_SIZE;
+pub use header_projection::HeaderProjection;
+#[cfg(feature = "runtime")]
+pub use local_app::LocalApp;
+#[cfg(feature = "runtime")]
+pub use local_server::{LocalServer, LocalServerHandle};
+pub use metrics::{debug_handler, Metrics, MetricsSnapshot};
+pub use mixed_replace::MixedReplaceStream;
+pub use multipart::{multipart_boundary, MultipartStream, PartHeaders};
+pub use path_matcher::PathMatcher;
+pub use preflight::PreflightResponder;
+pub use raw_bytes::RawBytes;
+#[cfg(feature = "runtime")]
+pub use replay::replay;
+pub use request_id::{request_id, RequestId};
+pub use response_builder::LocalResponseBuilder;
+pub use response_header_rules::ResponseHeaderRules;
+pub use retry::RetryPolicy;
+pub use route_table::{RouteEntry, RouteTable};
+pub use sse::SseEvent;
+#[cfg(feature = "runtime")]
+pub use sse_hub::SseHub;
+#[cfg(feature = "runtime")]
+pub use sse_stream::{SseAckSender, SseAckTracker, SseConfig, SseStream, HX_TRIGGER_EVENT};
+#[cfg(feature = "runtime")]
+pub use static_files::StaticFileResolver;
+pub use status_handlers::StatusHandlers;
+pub use streaming::StreamingResponse;
+pub use timeout::{Deadline, RequestStart};
+pub use uri_normalization::{TrailingSlash, UriNormalization};
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -73,9 +193,50 @@ pub enum Error {
 
     #[error("Could not parse body from LocalRequest")]
     RequestBodyParseError(#[from] http::Error),
+
+    #[error("No route matches {0}")]
+    NoMatchingRoute(String),
+
+    #[error("Could not parse multipart body: {0}")]
+    MultipartParseError(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Could not parse form body: {0}")]
+    FormParseError(String),
+
+    #[error("Could not parse query string: {0}")]
+    QueryParseError(String),
+
+    #[error("Request body is not valid UTF-8 for content type {0}")]
+    RequestBodyEncoding(String),
+
+    #[error("Could not decode request body: {0}")]
+    RequestBodyDecode(String),
+
+    #[error("Request body is larger than {0} bytes once decoded")]
+    RequestBodyTooLarge(usize),
+
+    #[error("Too many redirects or redirect loop at {0}")]
+    RedirectLoop(String),
+
+    #[cfg(feature = "msgpack")]
+    #[error("Could not encode MessagePack: {0}")]
+    MsgpackEncode(#[from] rmp_serde::encode::Error),
+
+    #[cfg(feature = "msgpack")]
+    #[error("Could not decode MessagePack: {0}")]
+    MsgpackDecode(#[from] rmp_serde::decode::Error),
 }
 
 /// Represents an HTTP request that can be processed by an Axum router.
+///
+/// `body` is a `String`, so only text bodies can cross the bridge and handlers
+/// always receive valid UTF-8. Bytes that were not valid UTF-8 on the JS side
+/// arrive replaced with `U+FFFD`, which cannot be told apart from a `U+FFFD`
+/// the user typed. Apps that never expect one can reject such bodies with
+/// [`LocalRequest::validate_body_encoding`].
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct LocalRequest {
     pub uri: String,
@@ -85,86 +246,517 @@ pub struct LocalRequest {
 }
 
 impl LocalRequest {
+    /// Looks up a header value, ignoring the case of `name`.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The `Accept-Language` header, for handlers doing i18n.
+    pub fn accept_language(&self) -> Option<&str> {
+        self.header("accept-language")
+    }
+
+    /// Renames every header to canonical Title-Case, e.g. `content-type` to
+    /// `Content-Type`, for code that compares names case-sensitively.
+    ///
+    /// This only affects the names in `headers`, as seen by request hooks,
+    /// recordings or a proxy forwarding the request. The `HeaderMap` handed to
+    /// axum always stores names lowercased.
+    pub fn title_case_headers(&mut self) {
+        self.headers = std::mem::take(&mut self.headers)
+            .into_iter()
+            .map(|(name, value)| (title_case(&name), value))
+            .collect();
+    }
+
+    /// The path component of the URI, without query string or fragment.
+    pub fn path(&self) -> &str {
+        let end = self.uri.find(['?', '#']).unwrap_or(self.uri.len());
+        &self.uri[..end]
+    }
+
+    /// The request line as it would appear on the wire, e.g.
+    /// `GET /items?page=2 HTTP/1.1`, for access logs.
+    pub fn request_line(&self) -> String {
+        let target = self.uri.split('#').next().unwrap_or_default();
+        format!("{} {} HTTP/1.1", self.method.to_uppercase(), target)
+    }
+
+    #[cfg(feature = "runtime")]
     pub async fn send_to_router(self, router: &mut Router) -> LocalResponse {
         match self.to_axum_request() {
-            Ok(request) => match router.call(request).await {
-                Ok(response) => LocalResponse::from_response(response).await,
-                Err(error) => LocalResponse::internal_server_error(error),
-            },
-            Err(error) => LocalResponse::internal_server_error(error),
+            Ok(request) => call_router(router, request).await,
+            Err(error) => LocalResponse::request_error(error),
         }
     }
 
-    fn to_axum_request(&self) -> Result<http::Request<Body>, Error> {
-        let uri = self.uri.to_string();
+    #[cfg(feature = "runtime")]
+    /// Sends the request from synchronous code, blocking on the runtime behind
+    /// `handle` instead of building a new one.
+    ///
+    /// Like [`Handle::block_on`](tokio::runtime::Handle::block_on), this panics
+    /// when called from inside an async context; use
+    /// [`LocalRequest::send_to_router`] there.
+    pub fn send_to_router_on(
+        self,
+        router: &mut Router,
+        handle: tokio::runtime::Handle,
+    ) -> LocalResponse {
+        handle.block_on(self.send_to_router(router))
+    }
+
+    /// Rejects a text body containing `U+FFFD`, the mark left by lossy UTF-8
+    /// decoding, with [`Error::RequestBodyEncoding`]. Not applied by the send
+    /// methods, since `U+FFFD` is also valid input; the dispatcher applies it
+    /// with `Dispatcher::with_body_encoding_validation`.
+    pub fn validate_body_encoding(&self) -> Result<(), Error> {
+        let Some(body) = &self.body else {
+            return Ok(());
+        };
+        let content_type = self.header("content-type").unwrap_or_default();
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        let is_text = mime.starts_with("text/")
+            || mime == "application/x-www-form-urlencoded"
+            || mime == "application/json"
+            || mime.ends_with("+json")
+            || mime.ends_with("xml");
+        if is_text && body.contains(char::REPLACEMENT_CHARACTER) {
+            return Err(Error::RequestBodyEncoding(mime));
+        }
+        Ok(())
+    }
+
+    /// Builds the `http::Request` that the send methods hand to the router.
+    /// Available without the `runtime` feature, for code that only needs to
+    /// build requests.
+    pub fn to_axum_request(&self) -> Result<http::Request<Body>, Error> {
+        // Fragments are client-side only and never sent to a server.
+        let uri = match self.uri.split_once('#') {
+            Some((uri, _fragment)) => uri.to_string(),
+            None => self.uri.to_string(),
+        };
         let mut request_builder = match self.method.to_uppercase().as_str() {
             "GET" => Ok(Request::get(uri)),
             "POST" => Ok(Request::post(uri)),
             "PUT" => Ok(Request::put(uri)),
             "DELETE" => Ok(Request::delete(uri)),
             "PATCH" => Ok(Request::patch(uri)),
+            "HEAD" => Ok(Request::head(uri)),
+            "OPTIONS" => Ok(Request::options(uri)),
             _ => Err(Error::RequestMethodParseError(self.method.to_string())),
         }?;
 
+        #[cfg(feature = "gzip")]
+        let inflated = match (&self.body, self.header("content-encoding")) {
+            (Some(body), Some(encoding)) if encoding.trim().eq_ignore_ascii_case("gzip") => {
+                Some(gzip::inflate_body(body)?)
+            }
+            _ => None,
+        };
+        #[cfg(not(feature = "gzip"))]
+        let inflated: Option<Vec<u8>> = None;
+
         for (key, value) in self.headers.iter() {
+            // The whole body is already here, so there is nothing to wait for
+            // and no interim response to send.
+            if key.eq_ignore_ascii_case("expect")
+                && value.trim().eq_ignore_ascii_case("100-continue")
+            {
+                continue;
+            }
+            // Both describe the compressed body, not the one handlers see.
+            if inflated.is_some()
+                && (key.eq_ignore_ascii_case("content-encoding")
+                    || key.eq_ignore_ascii_case("content-length"))
+            {
+                continue;
+            }
             request_builder = request_builder.header(key, value);
         }
 
-        let request = match &self.body {
+        let declared_length = inflated.is_none() && self.header("content-length").is_some();
+        let body = inflated.or_else(|| self.body.as_ref().map(|body| body.clone().into_bytes()));
+        let mut request = match body {
             None => request_builder.body(Body::empty()),
-            Some(body) => request_builder.body(body.to_string().into()),
+            Some(body) => {
+                // A real Content-Length lets size limits such as axum's
+                // `DefaultBodyLimit` reject oversized bodies with a 413 up front.
+                if !declared_length {
+                    request_builder =
+                        request_builder.header(http::header::CONTENT_LENGTH, body.len());
+                }
+                request_builder.body(body.into())
+            }
         }?;
+        request
+            .extensions_mut()
+            .insert(RequestStart(std::time::Instant::now()));
 
         Ok(request)
     }
 }
 
+fn title_case(name: &str) -> String {
+    name.split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => {
+                    first.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase()
+                }
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(feature = "runtime")]
+pub(crate) async fn call_router(router: &mut Router, request: Request<Body>) -> LocalResponse {
+    let span = telemetry::request_span(&request);
+    let response = async {
+        match router.call(request).await {
+            Ok(response) => LocalResponse::from_response(response).await,
+            Err(error) => LocalResponse::internal_server_error(error),
+        }
+    }
+    .instrument(span.clone())
+    .await;
+    telemetry::record_status(&span, response.status_code);
+    response
+}
+
+/// Converts a raw header value into a string that is safe to serialize over IPC.
+///
+/// Bytes that are not valid UTF-8 are replaced with `U+FFFD` and control
+/// characters other than tab are dropped.
+pub(crate) fn sanitize_header_value(value: &[u8]) -> String {
+    String::from_utf8_lossy(value)
+        .chars()
+        .filter(|c| *c == '\t' || !c.is_control())
+        .collect()
+}
+
+/// Folds response headers into the one-value-per-name map used by
+/// [`LocalResponse`] and [`StreamingResponse`], dropping hop-by-hop headers and
+/// keeping every `Set-Cookie`.
+pub(crate) fn fold_headers(response_headers: &http::HeaderMap) -> BTreeMap<String, String> {
+    let mut headers: BTreeMap<String, String> = BTreeMap::new();
+    for (key, value) in response_headers {
+        // Hop-by-hop headers describe a connection the webview never sees.
+        if key == http::header::CONNECTION || key == "keep-alive" {
+            continue;
+        }
+        let value = sanitize_header_value(value.as_bytes());
+        match headers.get_mut(key.as_str()) {
+            // Each cookie needs its own line; other repeated headers are
+            // rare here and the last one wins.
+            Some(existing) if key == http::header::SET_COOKIE => {
+                existing.push('\n');
+                existing.push_str(&value);
+            }
+            // Every policy applies, and a comma-separated list of
+            // policies means the same as repeating the header.
+            Some(existing)
+                if key == http::header::CONTENT_SECURITY_POLICY
+                    || key == http::header::CONTENT_SECURITY_POLICY_REPORT_ONLY =>
+            {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            }
+            // Kept so they can be serialized as separate pairs.
+            #[cfg(feature = "header-pairs")]
+            Some(existing) => {
+                existing.push('\n');
+                existing.push_str(&value);
+            }
+            _ => {
+                headers.insert(key.to_string(), value);
+            }
+        }
+    }
+    headers
+}
+
+/// A writer that only counts the bytes written to it, for measuring
+/// serialized sizes without allocating.
+pub(crate) struct ByteCounter(pub(crate) usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 /// Represents an HTTP response returned from an Axum router.
+///
+/// Headers are kept in a sorted map so the serialized form is stable.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LocalResponse {
     pub status_code: u16,
+    #[cfg_attr(feature = "msgpack", serde(with = "serde_bytes"))]
     pub body: Vec<u8>,
-    pub headers: HashMap<String, String>,
+    #[cfg_attr(feature = "header-pairs", serde(with = "header_pairs"))]
+    pub headers: BTreeMap<String, String>,
+    /// Set when the body has been moved into a [`BodyStore`]; `body` is then empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_token: Option<BodyToken>,
+    /// How the JS client should treat the body.
+    #[serde(default)]
+    pub response_kind: ResponseKind,
+    /// `true` when this crate produced the response itself, e.g. because the
+    /// request could not be built or timed out, rather than a handler.
+    #[serde(default)]
+    pub generated_by_crate: bool,
+    /// Named binary blobs sent alongside the body, e.g. a generated chart the
+    /// JS client turns into an object URL, saving a second request.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<(String, Vec<u8>)>,
+}
+
+/// Tells the JS client whether a response is a complete document or a stream,
+/// so it doesn't have to sniff the content type.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseKind {
+    #[default]
+    Normal,
+    /// A `text/event-stream` body.
+    Sse,
+    /// An `application/x-ndjson` body, read one JSON value per line.
+    NdjsonStream,
+    /// A `multipart/x-mixed-replace` body, where each part replaces the last.
+    MixedReplace,
+    /// Any other body produced as a stream of unknown length.
+    Stream,
+}
+
+impl ResponseKind {
+    pub(crate) fn detect(headers: &BTreeMap<String, String>, streamed: bool) -> Self {
+        let content_type = headers.get("content-type").map(String::as_str);
+        match content_type.unwrap_or_default() {
+            value if value.starts_with("text/event-stream") => ResponseKind::Sse,
+            value if value.starts_with("application/x-ndjson") => ResponseKind::NdjsonStream,
+            value if value.starts_with("multipart/x-mixed-replace") => ResponseKind::MixedReplace,
+            _ if streamed => ResponseKind::Stream,
+            _ => ResponseKind::Normal,
+        }
+    }
+}
+
+impl Default for LocalResponse {
+    fn default() -> Self {
+        LocalResponse {
+            status_code: 200,
+            body: Vec::new(),
+            headers: BTreeMap::new(),
+            body_token: None,
+            response_kind: ResponseKind::Normal,
+            generated_by_crate: false,
+            attachments: Vec::new(),
+        }
+    }
 }
 
 impl LocalResponse {
+    #[cfg(feature = "runtime")]
+    /// The answer to a request that could not be built: `413` for a body that
+    /// is too large, `500` otherwise.
+    pub(crate) fn request_error(error: Error) -> Self {
+        match error {
+            Error::RequestBodyTooLarge(_) => LocalResponse {
+                status_code: 413,
+                body: error.to_string().into(),
+                generated_by_crate: true,
+                ..Default::default()
+            },
+            error => LocalResponse::internal_server_error(error),
+        }
+    }
+
     pub fn internal_server_error(error: impl Display) -> Self {
         let error_message = format!("An error occured: {}", error);
         LocalResponse {
             status_code: 500,
             body: error_message.into(),
             headers: Default::default(),
+            generated_by_crate: true,
+            ..Default::default()
         }
     }
+
+    /// Looks up a header value, ignoring the case of `name`.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn with_attachment(mut self, name: impl Into<String>, data: Vec<u8>) -> Self {
+        self.attachments.push((name.into(), data));
+        self
+    }
+
+    /// The declared `Content-Length`, which can differ from `body.len()`, e.g.
+    /// for `HEAD` responses. `None` if the header is missing or malformed.
+    pub fn content_length(&self) -> Option<u64> {
+        self.header("content-length")?.trim().parse().ok()
+    }
+
+    /// Overwrites `Content-Length` with the real body length when a handler
+    /// declared a different one, since a wrong length can make the webview
+    /// truncate the body or wait for bytes that never come. Not meant for
+    /// `HEAD` responses, whose declared length differs on purpose.
+    pub fn reconcile_content_length(self) -> Self {
+        let actual = self.body.len() as u64;
+        match self.header("content-length") {
+            Some(declared) if declared.trim().parse() != Ok(actual) => {
+                tracing::debug!(declared, actual, "correcting content-length");
+                self.with_header("content-length", actual.to_string())
+            }
+            _ => self,
+        }
+    }
+
+    /// Sets a header, replacing any existing value regardless of the case of its
+    /// name. Names are stored lowercased, matching [`LocalResponse::from_response`].
+    pub fn with_header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers
+            .retain(|key, _| !key.eq_ignore_ascii_case(name));
+        self.headers.insert(name.to_ascii_lowercase(), value.into());
+        self
+    }
+
+    /// Removes a header regardless of the case of its name.
+    pub fn without_header(mut self, name: &str) -> Self {
+        self.headers
+            .retain(|key, _| !key.eq_ignore_ascii_case(name));
+        self
+    }
+
+    pub fn with_status(mut self, status_code: u16) -> Self {
+        self.status_code = status_code;
+        self
+    }
+
+    /// Whether the status is a redirect (`301`, `302`, `303`, `307` or `308`).
+    pub fn is_redirect(&self) -> bool {
+        matches!(self.status_code, 301 | 302 | 303 | 307 | 308)
+    }
+
+    /// The redirect target from the `Location` header, if present.
+    pub fn location(&self) -> Option<&str> {
+        self.header("location")
+    }
+
+    /// Borrows the body as a `&str` without copying, or `None` if it is not valid UTF-8.
+    pub fn body_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.body).ok()
+    }
+
+    /// The size in bytes of this response serialized as JSON, as it crosses
+    /// the IPC bridge, without building the payload. Note that `body` is
+    /// serialized as an array of numbers, so a large body costs about four
+    /// times its length.
+    ///
+    /// This always measures JSON. Apps sending `to_msgpack` bytes instead
+    /// should use `ipc_payload_size_msgpack`, available with the `msgpack`
+    /// feature.
+    pub fn ipc_payload_size(&self) -> usize {
+        let mut counter = ByteCounter(0);
+        serde_json::to_writer(&mut counter, self).map_or(0, |_| counter.0)
+    }
+
+    /// A one-line description for logs, e.g.
+    /// `200 OK | content-type: text/html | 1234 bytes | "<div>..."`. The body
+    /// preview is cut after 40 characters and omitted for binary bodies.
+    pub fn summary(&self) -> String {
+        const PREVIEW_CHARS: usize = 40;
+
+        let reason = http::StatusCode::from_u16(self.status_code)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or_default();
+        let mut parts = vec![format!("{} {}", self.status_code, reason)
+            .trim_end()
+            .to_string()];
+        if let Some(content_type) = self.header("content-type") {
+            parts.push(format!("content-type: {}", content_type));
+        }
+        parts.push(format!("{} bytes", self.body.len()));
+        match self.body_str() {
+            Some("") => {}
+            Some(body) => {
+                let mut preview: String = body.chars().take(PREVIEW_CHARS).collect();
+                if preview.len() < body.len() {
+                    preview.push_str("...");
+                }
+                parts.push(format!("{:?}", preview));
+            }
+            None => parts.push("<binary>".to_string()),
+        }
+        parts.join(" | ")
+    }
 }
 
+#[cfg(feature = "runtime")]
 impl LocalResponse {
     pub async fn from_response(response: Response) -> Self {
         let code = response.status();
         let response_headers = response.headers().clone();
+        let streamed = response.body().size_hint().exact().is_none();
         let bytes_result = axum::body::to_bytes(response.into_body(), usize::MAX).await;
 
-        let mut headers: HashMap<String, String> = HashMap::new();
-        for (key, value) in response_headers.iter() {
-            headers.insert(key.to_string(), value.to_str().unwrap().to_string());
-        }
+        let mut headers = fold_headers(&response_headers);
+        let response_kind = ResponseKind::detect(&headers, streamed);
 
         match bytes_result {
-            Ok(data) => LocalResponse {
-                status_code: code.as_u16(),
-                body: data.to_vec(),
-                headers,
-            },
+            Ok(data) => {
+                // Streamed bodies arrive without a length; once collected the
+                // length is known, so report it the way a server would.
+                // Statuses that never carry a body must not declare one.
+                let may_have_body = !matches!(code.as_u16(), 100..=199 | 204 | 304);
+                if streamed
+                    && may_have_body
+                    && response_kind != ResponseKind::Sse
+                    && !headers.contains_key("content-length")
+                {
+                    headers.remove("transfer-encoding");
+                    headers.insert("content-length".to_string(), data.len().to_string());
+                }
+                LocalResponse {
+                    status_code: code.as_u16(),
+                    body: data.to_vec(),
+                    headers,
+                    response_kind,
+                    ..Default::default()
+                }
+            }
             Err(_) => LocalResponse {
                 status_code: code.as_u16(),
                 body: Vec::new(),
                 headers: headers.clone(),
+                response_kind,
+                ..Default::default()
             },
         }
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "runtime"))]
 mod tests {
     use super::*;
     use axum::{
@@ -184,6 +776,136 @@ mod tests {
     mod local_request_tests {
         use super::*;
 
+        #[test]
+        fn test_invalid_utf8_text_body_is_rejected() {
+            let mut headers = HashMap::new();
+            headers.insert("Content-Type".to_string(), "text/plain".to_string());
+            let request = LocalRequest {
+                uri: "/echo".to_string(),
+                method: "POST".to_string(),
+                body: Some(String::from_utf8_lossy(b"caf\xe9").into_owned()),
+                headers,
+            };
+
+            assert!(matches!(
+                request.validate_body_encoding(),
+                Err(Error::RequestBodyEncoding(mime)) if mime == "text/plain"
+            ));
+        }
+
+        #[tokio::test]
+        async fn test_replacement_character_is_accepted_by_default() {
+            let mut headers = HashMap::new();
+            headers.insert("Content-Type".to_string(), "application/json".to_string());
+            let request = LocalRequest {
+                uri: "/echo".to_string(),
+                method: "POST".to_string(),
+                body: Some(r#"{"note":"unknown glyph \u{fffd}"}"#.to_string()),
+                headers,
+            };
+
+            let response = request.send_to_router(&mut create_test_router()).await;
+            assert_eq!(response.status_code, 200);
+            assert_eq!(
+                response.body_str(),
+                Some(r#"{"note":"unknown glyph \u{fffd}"}"#)
+            );
+        }
+
+        #[tokio::test]
+        async fn test_expect_continue_is_stripped() {
+            let mut router = Router::new().route(
+                "/upload",
+                post(|headers: http::HeaderMap, body: String| async move {
+                    format!("{} {}", headers.contains_key(http::header::EXPECT), body)
+                }),
+            );
+            let mut headers = HashMap::new();
+            headers.insert("Expect".to_string(), "100-continue".to_string());
+            let request = LocalRequest {
+                uri: "/upload".to_string(),
+                method: "POST".to_string(),
+                body: Some("data".to_string()),
+                headers,
+            };
+
+            let response = request.send_to_router(&mut router).await;
+            assert_eq!(response.status_code, 200);
+            assert_eq!(response.body_str(), Some("false data"));
+        }
+
+        #[test]
+        fn test_send_on_existing_runtime() {
+            let runtime = tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            let mut router = create_test_router();
+            let request = LocalRequest {
+                uri: "/test".to_string(),
+                method: "GET".to_string(),
+                body: None,
+                headers: HashMap::new(),
+            };
+
+            let response = request.send_to_router_on(&mut router, runtime.handle().clone());
+            assert_eq!(response.status_code, 200);
+            assert_eq!(response.body_str(), Some("Hello, World!"));
+        }
+
+        #[test]
+        fn test_title_case_headers() {
+            let mut headers = HashMap::new();
+            headers.insert("content-type".to_string(), "text/html".to_string());
+            headers.insert("X-REQUESTED-WITH".to_string(), "XMLHttpRequest".to_string());
+            headers.insert("hx-request".to_string(), "true".to_string());
+            let mut request = LocalRequest {
+                uri: "/".to_string(),
+                method: "GET".to_string(),
+                body: None,
+                headers,
+            };
+
+            request.title_case_headers();
+            let mut names: Vec<&str> = request.headers.keys().map(String::as_str).collect();
+            names.sort();
+            assert_eq!(names, ["Content-Type", "Hx-Request", "X-Requested-With"]);
+        }
+
+        #[tokio::test]
+        async fn test_default_body_limit_rejects_oversized_body() {
+            use axum::extract::DefaultBodyLimit;
+
+            let mut router = Router::new()
+                .route(
+                    "/upload",
+                    post(|body: String| async move { body.len().to_string() }),
+                )
+                .layer(DefaultBodyLimit::max(100));
+            let request = |size: usize| LocalRequest {
+                uri: "/upload".to_string(),
+                method: "POST".to_string(),
+                body: Some("x".repeat(size)),
+                headers: HashMap::new(),
+            };
+
+            let response = request(200).send_to_router(&mut router).await;
+            assert_eq!(response.status_code, 413);
+            let response = request(50).send_to_router(&mut router).await;
+            assert_eq!(response.body_str(), Some("50"));
+        }
+
+        #[test]
+        fn test_request_line() {
+            let request = LocalRequest {
+                uri: "/search?q=htmx&page=2#results".to_string(),
+                method: "get".to_string(),
+                body: None,
+                headers: HashMap::new(),
+            };
+            assert_eq!(request.request_line(), "GET /search?q=htmx&page=2 HTTP/1.1");
+        }
+
         #[tokio::test]
         async fn test_basic_get_request() {
             let mut router = create_test_router();
@@ -261,12 +983,93 @@ mod tests {
             assert_eq!(response.status_code, 200);
             assert_eq!(String::from_utf8(response.body).unwrap(), "test-value");
         }
+
+        #[tokio::test]
+        async fn test_fragment_is_stripped_from_uri() {
+            let mut router = Router::new().route(
+                "/page",
+                get(|uri: http::Uri| async move { uri.to_string() }),
+            );
+            let request = LocalRequest {
+                uri: "/page#frag".to_string(),
+                method: "GET".to_string(),
+                body: None,
+                headers: HashMap::new(),
+            };
+
+            let response = request.send_to_router(&mut router).await;
+            assert_eq!(response.status_code, 200);
+            assert_eq!(response.body_str(), Some("/page"));
+        }
+
+        #[tokio::test]
+        async fn test_content_length_is_set_from_body() {
+            let mut router = Router::new().route(
+                "/length",
+                post(|req: Request<Body>| async move {
+                    req.headers()
+                        .get(http::header::CONTENT_LENGTH)
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or("")
+                        .to_string()
+                }),
+            );
+
+            let body = "twelve bytes";
+            let request = LocalRequest {
+                uri: "/length".to_string(),
+                method: "POST".to_string(),
+                body: Some(body.to_string()),
+                headers: HashMap::new(),
+            };
+
+            let response = request.send_to_router(&mut router).await;
+            assert_eq!(response.body_str(), Some(body.len().to_string().as_str()));
+        }
     }
 
     mod local_response_tests {
         use super::*;
         use http::response::Builder;
 
+        #[test]
+        fn test_ipc_payload_size() {
+            let response = LocalResponse {
+                body: vec![200; 1000],
+                ..Default::default()
+            }
+            .with_header("content-type", "application/octet-stream");
+
+            let size = response.ipc_payload_size();
+            assert_eq!(size, serde_json::to_vec(&response).unwrap().len());
+            assert!(size > 4000);
+        }
+
+        #[test]
+        fn test_summary() {
+            let response = LocalResponse {
+                body: format!("<div>{}</div>", "x".repeat(100)).into_bytes(),
+                ..Default::default()
+            }
+            .with_header("Content-Type", "text/html");
+
+            assert_eq!(
+                response.summary(),
+                format!(
+                    "200 OK | content-type: text/html | 111 bytes | \"<div>{}...\"",
+                    "x".repeat(35)
+                )
+            );
+            assert_eq!(
+                LocalResponse {
+                    status_code: 204,
+                    ..Default::default()
+                }
+                .summary(),
+                "204 No Content | 0 bytes"
+            );
+        }
+
         #[tokio::test]
         async fn test_response_creation_with_body() {
             let response = Builder::new()
@@ -292,6 +1095,64 @@ mod tests {
             assert_eq!(local_response.headers.get("x-test").unwrap(), "test-value");
         }
 
+        #[tokio::test]
+        async fn test_streamed_body_gets_content_length() {
+            let chunks: Vec<Result<&str, std::convert::Infallible>> =
+                vec![Ok("<p>one</p>"), Ok("<p>two</p>")];
+            let response = Builder::new()
+                .status(200)
+                .header("content-type", "text/html")
+                .header("transfer-encoding", "chunked")
+                .body(Body::from_stream(futures_util::stream::iter(chunks)))
+                .unwrap();
+
+            let local_response = LocalResponse::from_response(response).await;
+            assert_eq!(local_response.header("content-length"), Some("20"));
+            assert_eq!(local_response.header("transfer-encoding"), None);
+            assert_eq!(local_response.response_kind, ResponseKind::Stream);
+        }
+
+        #[tokio::test]
+        async fn test_no_content_gets_no_content_length() {
+            let chunks: Vec<Result<&str, std::convert::Infallible>> = Vec::new();
+            let response = Builder::new()
+                .status(http::StatusCode::NO_CONTENT)
+                .body(Body::from_stream(futures_util::stream::iter(chunks)))
+                .unwrap();
+
+            let local_response = LocalResponse::from_response(response).await;
+            assert_eq!(local_response.status_code, 204);
+            assert_eq!(local_response.header("content-length"), None);
+        }
+
+        #[test]
+        fn test_content_length_is_read_from_header() {
+            let head = LocalResponse::default().with_header("Content-Length", "1024");
+            assert_eq!(head.content_length(), Some(1024));
+            assert!(head.body.is_empty());
+
+            assert_eq!(LocalResponse::default().content_length(), None);
+            let malformed = LocalResponse::default().with_header("content-length", "lots");
+            assert_eq!(malformed.content_length(), None);
+        }
+
+        #[test]
+        fn test_attachments_round_trip_through_serde() {
+            let response = LocalResponse::default()
+                .with_header("content-type", "text/html")
+                .with_attachment("chart.png", vec![0x89, b'P', b'N', b'G']);
+
+            let json = serde_json::to_string(&response).unwrap();
+            let decoded: LocalResponse = serde_json::from_str(&json).unwrap();
+            assert_eq!(
+                decoded.attachments,
+                vec![("chart.png".to_string(), vec![0x89, b'P', b'N', b'G'])]
+            );
+
+            let plain = serde_json::to_value(LocalResponse::default()).unwrap();
+            assert!(plain.get("attachments").is_none());
+        }
+
         #[tokio::test]
         async fn test_internal_server_error() {
             let error_message = "Test error";
@@ -302,6 +1163,117 @@ mod tests {
                 .unwrap()
                 .contains(error_message));
             assert!(response.headers.is_empty());
+            assert!(response.generated_by_crate);
+        }
+
+        #[tokio::test]
+        async fn test_handler_error_is_not_generated_by_crate() {
+            let response = Builder::new()
+                .status(500)
+                .body(Body::from("handler failed"))
+                .unwrap();
+
+            let local_response = LocalResponse::from_response(response).await;
+            assert_eq!(local_response.status_code, 500);
+            assert!(!local_response.generated_by_crate);
+
+            let request = LocalRequest {
+                uri: "/test".to_string(),
+                method: "BREW".to_string(),
+                body: None,
+                headers: HashMap::new(),
+            };
+            let response = request.send_to_router(&mut create_test_router()).await;
+            assert!(response.generated_by_crate);
+        }
+
+        #[tokio::test]
+        async fn test_body_str_borrows_utf8_body() {
+            let mut router = create_test_router();
+            let request = LocalRequest {
+                uri: "/test".to_string(),
+                method: "GET".to_string(),
+                body: None,
+                headers: HashMap::new(),
+            };
+
+            let response = request.send_to_router(&mut router).await;
+            assert_eq!(response.body_str(), Some("Hello, World!"));
+
+            let invalid = LocalResponse {
+                status_code: 200,
+                body: vec![0xff, 0xfe],
+                headers: BTreeMap::new(),
+                ..Default::default()
+            };
+            assert_eq!(invalid.body_str(), None);
+        }
+
+        #[tokio::test]
+        async fn test_invalid_header_values_are_sanitized() {
+            let response = Builder::new()
+                .status(200)
+                .header(
+                    "X-Latin1",
+                    http::HeaderValue::from_bytes(b"caf\xe9").unwrap(),
+                )
+                .header("X-Tabbed", "a\tb")
+                .body(Body::empty())
+                .unwrap();
+
+            let local_response = LocalResponse::from_response(response).await;
+            assert_eq!(local_response.header("x-latin1"), Some("caf\u{fffd}"));
+            assert_eq!(local_response.header("x-tabbed"), Some("a\tb"));
+            assert_eq!(sanitize_header_value(b"bad\0value\x1b"), "badvalue");
+        }
+
+        #[test]
+        fn test_fluent_header_modifications() {
+            let response = LocalResponse::internal_server_error("boom")
+                .with_status(418)
+                .with_header("X-Custom", "one")
+                .with_header("x-custom", "two")
+                .with_header("X-Removed", "gone")
+                .without_header("x-REMOVED");
+
+            assert_eq!(response.status_code, 418);
+            assert_eq!(response.headers.len(), 1);
+            assert_eq!(response.header("X-CUSTOM"), Some("two"));
+            assert_eq!(response.header("x-removed"), None);
+        }
+
+        #[tokio::test]
+        async fn test_redirect_location() {
+            let response = Builder::new()
+                .status(302)
+                .header("Location", "/target")
+                .body(Body::empty())
+                .unwrap();
+
+            let local_response = LocalResponse::from_response(response).await;
+            assert!(local_response.is_redirect());
+            assert_eq!(local_response.location(), Some("/target"));
+        }
+
+        #[tokio::test]
+        async fn test_serialized_headers_are_deterministic() {
+            let build = |names: &[&str]| {
+                let mut builder = Builder::new().status(200);
+                for name in names {
+                    builder = builder.header(*name, "value");
+                }
+                builder.body(Body::empty()).unwrap()
+            };
+
+            let first =
+                LocalResponse::from_response(build(&["x-zeta", "x-alpha", "X-Middle"])).await;
+            let second =
+                LocalResponse::from_response(build(&["X-Middle", "x-zeta", "x-alpha"])).await;
+
+            let first_json = serde_json::to_vec(&first).unwrap();
+            assert_eq!(first_json, serde_json::to_vec(&first).unwrap());
+            assert_eq!(first_json, serde_json::to_vec(&second).unwrap());
+            assert_eq!(second.header("x-middle"), Some("value"));
         }
     }
 
@@ -310,7 +1282,7 @@ mod tests {
 
         #[tokio::test]
         async fn test_all_valid_methods() {
-            let methods = vec!["GET", "POST", "PUT", "DELETE", "PATCH"];
+            let methods = vec!["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"];
 
             for method in methods {
                 let request = LocalRequest {
@@ -337,3 +1309,26 @@ mod tests {
         }
     }
 }
+
+#[cfg(all(test, not(feature = "runtime")))]
+mod request_building_tests {
+    use super::*;
+
+    #[test]
+    fn test_request_builds_without_runtime() {
+        let mut headers = HashMap::new();
+        headers.insert("HX-Request".to_string(), "true".to_string());
+        let request = LocalRequest {
+            uri: "/todos?page=2#top".to_string(),
+            method: "post".to_string(),
+            body: Some("title=milk".to_string()),
+            headers,
+        };
+
+        let request = request.to_axum_request().unwrap();
+        assert_eq!(request.method(), http::Method::POST);
+        assert_eq!(request.uri(), "/todos?page=2");
+        assert_eq!(request.headers()["hx-request"], "true");
+        assert_eq!(request.headers()[http::header::CONTENT_LENGTH], "10");
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{Dispatcher, LocalRequest, LocalResponse};
+use axum::Router;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Owns a router and serves requests without a global lock.
+///
+/// `Router` is cheap to clone, and every clone shares the same handlers and
+/// layers, so each request gets its own copy instead of waiting on a
+/// `Mutex<Router>`. Shared resources such as database pools should be attached
+/// with an `Extension` (or `with_state`) layer, which survives the clone:
+///
+/// ```rust
+/// use axum::{routing::get, Extension, Router};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+/// use tauri_axum_htmx::LocalApp;
+///
+/// struct Pool {
+///     queries: AtomicUsize,
+/// }
+///
+/// let pool = Arc::new(Pool { queries: AtomicUsize::new(0) });
+/// let router = Router::new()
+///     .route(
+///         "/",
+///         get(|Extension(pool): Extension<Arc<Pool>>| async move {
+///             pool.queries.fetch_add(1, Ordering::SeqCst).to_string()
+///         }),
+///     )
+///     .layer(Extension(pool));
+///
+/// // Manage this in Tauri state instead of an `Arc<Mutex<Router>>`.
+/// let app = LocalApp::new(router);
+/// ```
+///
+/// Call [`LocalApp::shutdown`] when the Tauri app is exiting to let in-flight
+/// requests finish.
+#[derive(Clone, Debug)]
+pub struct LocalApp {
+    router: Router,
+    dispatcher: Dispatcher,
+    lifecycle: Arc<Lifecycle>,
+}
+
+#[derive(Debug, Default)]
+struct Lifecycle {
+    shutting_down: AtomicBool,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+/// Counts a request as in flight until dropped.
+struct InFlight<'a>(&'a Lifecycle);
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+impl LocalApp {
+    pub fn new(router: Router) -> Self {
+        LocalApp {
+            router,
+            dispatcher: Dispatcher::default(),
+            lifecycle: Default::default(),
+        }
+    }
+
+    /// Sends every request through `dispatcher` instead of the default one.
+    pub fn with_dispatcher(mut self, dispatcher: Dispatcher) -> Self {
+        self.dispatcher = dispatcher;
+        self
+    }
+
+    /// Sends the request to a clone of the router. Once shutdown has begun this
+    /// answers `503` without calling the router.
+    pub async fn send(&self, request: LocalRequest) -> LocalResponse {
+        self.lifecycle.in_flight.fetch_add(1, Ordering::SeqCst);
+        let _in_flight = InFlight(&self.lifecycle);
+        if self.is_shutting_down() {
+            return LocalResponse {
+                status_code: 503,
+                body: "The application is shutting down".into(),
+                generated_by_crate: true,
+                ..Default::default()
+            };
+        }
+
+        let mut router = self.router.clone();
+        self.dispatcher.send(request, &mut router).await
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.lifecycle.shutting_down.load(Ordering::SeqCst)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.lifecycle.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Stops accepting new requests and waits up to `grace_period` for the ones
+    /// already running to finish. Returns `true` if they all completed in time.
+    pub async fn shutdown(&self, grace_period: Duration) -> bool {
+        self.lifecycle.shutting_down.store(true, Ordering::SeqCst);
+        let drained = async {
+            loop {
+                let idle = self.lifecycle.idle.notified();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        };
+        tokio::time::timeout(grace_period, drained).await.is_ok()
+    }
+}
+
+impl LocalRequest {
+    /// Sends the request to a clone of `router`, so callers can share a router
+    /// without locking it for the duration of the request.
+    pub async fn send_to_router_shared(self, router: &Router) -> LocalResponse {
+        let mut router = router.clone();
+        self.send_to_router(&mut router).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Extension};
+    use std::collections::HashMap;
+
+    fn request_to(uri: &str) -> LocalRequest {
+        LocalRequest {
+            uri: uri.to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    fn request() -> LocalRequest {
+        LocalRequest {
+            uri: "/count".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_share_extension_state() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let router = Router::new()
+            .route(
+                "/count",
+                get(
+                    |Extension(counter): Extension<Arc<AtomicUsize>>| async move {
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        "counted"
+                    },
+                ),
+            )
+            .layer(Extension(counter.clone()));
+        let app = LocalApp::new(router.clone());
+        let cloned_app = app.clone();
+
+        let (first, second, third) = tokio::join!(
+            app.send(request()),
+            cloned_app.send(request()),
+            request().send_to_router_shared(&router),
+        );
+        assert_eq!(first.status_code, 200);
+        assert_eq!(second.status_code, 200);
+        assert_eq!(third.status_code, 200);
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_in_flight_requests() {
+        let router = Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    "finished"
+                }),
+            )
+            .route("/fast", get(|| async { "fast" }));
+        let app = LocalApp::new(router);
+
+        let slow = app.send(request_to("/slow"));
+        let shutdown_then_send = async {
+            while app.in_flight() == 0 {
+                tokio::task::yield_now().await;
+            }
+            // `join!` polls in order, so shutdown has begun before the next send.
+            tokio::join!(
+                app.shutdown(Duration::from_secs(5)),
+                app.send(request_to("/fast"))
+            )
+        };
+
+        let (slow, (drained, rejected)) = tokio::join!(slow, shutdown_then_send);
+        assert_eq!(slow.status_code, 200);
+        assert_eq!(slow.body_str(), Some("finished"));
+        assert_eq!(rejected.status_code, 503);
+        assert!(drained);
+        assert_eq!(app.in_flight(), 0);
+    }
+}
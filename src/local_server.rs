@@ -0,0 +1,97 @@
+use crate::{LocalRequest, LocalResponse};
+use axum::Router;
+use tokio::sync::{mpsc, oneshot};
+
+type Job = (LocalRequest, oneshot::Sender<LocalResponse>);
+
+/// Runs a router on its own task, which owns it outright and serves requests
+/// one at a time from a channel.
+///
+/// This is an alternative to [`LocalApp`](crate::LocalApp) for apps that
+/// would rather not clone the router per request. Requests are sent through
+/// the returned [`LocalServerHandle`]; the task stops once every handle has
+/// been dropped.
+///
+/// ```rust,no_run
+/// use axum::{routing::get, Router};
+/// use tauri_axum_htmx::LocalServer;
+///
+/// # async fn run() {
+/// let handle = LocalServer::spawn(Router::new().route("/", get(|| async { "hi" })));
+/// // Manage `handle` in Tauri state and call `handle.send(request)` from the command.
+/// # }
+/// ```
+pub struct LocalServer {
+    router: Router,
+    jobs: mpsc::UnboundedReceiver<Job>,
+}
+
+impl LocalServer {
+    /// Spawns the server on the current Tokio runtime.
+    pub fn spawn(router: Router) -> LocalServerHandle {
+        let (sender, jobs) = mpsc::unbounded_channel();
+        tokio::spawn(LocalServer { router, jobs }.run());
+        LocalServerHandle { sender }
+    }
+
+    async fn run(mut self) {
+        while let Some((request, reply)) = self.jobs.recv().await {
+            let response = request.send_to_router(&mut self.router).await;
+            // The caller may have given up waiting; nothing to do then.
+            let _ = reply.send(response);
+        }
+    }
+}
+
+/// A cheap, cloneable handle for sending requests to a [`LocalServer`].
+#[derive(Clone, Debug)]
+pub struct LocalServerHandle {
+    sender: mpsc::UnboundedSender<Job>,
+}
+
+impl LocalServerHandle {
+    /// Sends `request` to the server and waits for its response. Answers `500`
+    /// if the server task is no longer running.
+    pub async fn send(&self, request: LocalRequest) -> LocalResponse {
+        let (reply, response) = oneshot::channel();
+        if self.sender.send((request, reply)).is_err() {
+            return LocalResponse::internal_server_error("local server has stopped");
+        }
+        response.await.unwrap_or_else(|_| {
+            LocalResponse::internal_server_error("local server dropped the request")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::Path, routing::get};
+    use std::collections::HashMap;
+
+    fn get_request(uri: &str) -> LocalRequest {
+        LocalRequest {
+            uri: uri.to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_through_handle() {
+        let router = Router::new().route(
+            "/greet/{name}",
+            get(|Path(name): Path<String>| async move { format!("hello {}", name) }),
+        );
+        let handle = LocalServer::spawn(router);
+        let other = handle.clone();
+
+        let (first, second) = tokio::join!(
+            handle.send(get_request("/greet/ada")),
+            other.send(get_request("/greet/grace")),
+        );
+        assert_eq!(first.body_str(), Some("hello ada"));
+        assert_eq!(second.body_str(), Some("hello grace"));
+    }
+}
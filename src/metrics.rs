@@ -0,0 +1,107 @@
+use axum::routing::{get, MethodRouter};
+use axum::Json;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Request counters for the bridge, shared between a
+/// [`Dispatcher`](crate::Dispatcher) that records them and a
+/// [`debug_handler`] that serves them. Cloning shares the same counters.
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    inner: Arc<Counters>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    requests: AtomicU64,
+    errors: AtomicU64,
+    total_latency_us: AtomicU64,
+}
+
+/// A point-in-time copy of [`Metrics`], as served by [`debug_handler`].
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct MetricsSnapshot {
+    pub requests: u64,
+    /// Responses with a `5xx` status.
+    pub errors: u64,
+    pub average_latency_ms: f64,
+    pub version: &'static str,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, status_code: u16, latency: Duration) {
+        self.inner.requests.fetch_add(1, Ordering::Relaxed);
+        if status_code >= 500 {
+            self.inner.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        self.inner
+            .total_latency_us
+            .fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let requests = self.inner.requests.load(Ordering::Relaxed);
+        let total_latency_us = self.inner.total_latency_us.load(Ordering::Relaxed);
+        MetricsSnapshot {
+            requests,
+            errors: self.inner.errors.load(Ordering::Relaxed),
+            average_latency_ms: if requests == 0 {
+                0.0
+            } else {
+                total_latency_us as f64 / requests as f64 / 1000.0
+            },
+            version: env!("CARGO_PKG_VERSION"),
+        }
+    }
+}
+
+/// A `GET` handler serving `metrics` as JSON, for a built-in debug screen.
+///
+/// ```rust
+/// use axum::Router;
+/// use tauri_axum_htmx::{debug_handler, Dispatcher, Metrics};
+///
+/// let metrics = Metrics::new();
+/// let dispatcher = Dispatcher::new().with_metrics(metrics.clone());
+/// let router: Router = Router::new().route("/_debug", debug_handler(metrics));
+/// ```
+pub fn debug_handler<S>(metrics: Metrics) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    get(move || async move { Json(metrics.snapshot()) })
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use crate::{Dispatcher, LocalRequest};
+    use axum::Router;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_debug_handler_reports_request_count() {
+        let metrics = Metrics::new();
+        let dispatcher = Dispatcher::new().with_metrics(metrics.clone());
+        let mut router = Router::new().route("/_debug", debug_handler(metrics));
+        let request = LocalRequest {
+            uri: "/_debug".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        dispatcher.send(request.clone(), &mut router).await;
+        let response = dispatcher.send(request, &mut router).await;
+        let json: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(json["requests"], 1);
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        assert!(json["average_latency_ms"].is_number());
+    }
+}
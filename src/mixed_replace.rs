@@ -0,0 +1,130 @@
+use axum::body::Body;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use futures_util::stream::{BoxStream, Stream, StreamExt};
+use std::convert::Infallible;
+
+const DEFAULT_BOUNDARY: &str = "frame";
+
+/// A `multipart/x-mixed-replace` stream that can be returned from a handler,
+/// e.g. for a live preview where each frame replaces the previous image.
+///
+/// Every frame is sent as a part with the stream's content type and its
+/// `Content-Length`. Like [`SseStream`](crate::SseStream), it is read through
+/// the bridge with
+/// [`LocalRequest::send_to_router_streaming`](crate::LocalRequest::send_to_router_streaming),
+/// and the response head has
+/// [`ResponseKind::MixedReplace`](crate::ResponseKind::MixedReplace).
+pub struct MixedReplaceStream {
+    frames: BoxStream<'static, Vec<u8>>,
+    content_type: String,
+    boundary: String,
+}
+
+impl MixedReplaceStream {
+    /// Streams `frames`, each one a complete body of `content_type`.
+    pub fn new(
+        content_type: impl Into<String>,
+        frames: impl Stream<Item = Vec<u8>> + Send + 'static,
+    ) -> Self {
+        MixedReplaceStream {
+            frames: frames.boxed(),
+            content_type: content_type.into(),
+            boundary: DEFAULT_BOUNDARY.to_string(),
+        }
+    }
+
+    /// Uses `boundary` instead of `frame` to separate the frames. It must not
+    /// occur in any frame.
+    pub fn with_boundary(mut self, boundary: impl Into<String>) -> Self {
+        self.boundary = boundary.into();
+        self
+    }
+
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+}
+
+/// Serializes one frame as a part, including its leading boundary.
+fn encode_frame(boundary: &str, content_type: &str, frame: &[u8]) -> Vec<u8> {
+    let mut part = format!(
+        "--{}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n",
+        boundary,
+        content_type,
+        frame.len()
+    )
+    .into_bytes();
+    part.extend_from_slice(frame);
+    part.extend_from_slice(b"\r\n");
+    part
+}
+
+impl IntoResponse for MixedReplaceStream {
+    fn into_response(self) -> Response {
+        let MixedReplaceStream {
+            frames,
+            content_type,
+            boundary,
+        } = self;
+        let header_value = format!("multipart/x-mixed-replace; boundary={}", boundary);
+        let parts = frames
+            .map(move |frame| Ok::<_, Infallible>(encode_frame(&boundary, &content_type, &frame)));
+
+        (
+            [
+                (header::CONTENT_TYPE, header_value),
+                (header::CACHE_CONTROL, "no-cache".to_string()),
+            ],
+            Body::from_stream(parts),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use crate::{LocalRequest, ResponseKind};
+    use axum::{routing::get, Router};
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_frames_are_separated_by_boundary() {
+        let mut router = Router::new().route(
+            "/preview",
+            get(|| async {
+                MixedReplaceStream::new(
+                    "image/png",
+                    futures_util::stream::iter(vec![
+                        b"\x89PNG one".to_vec(),
+                        b"\x89PNG two".to_vec(),
+                    ]),
+                )
+            }),
+        );
+        let request = LocalRequest {
+            uri: "/preview".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let mut response = request.send_to_router_streaming(&mut router).await;
+        let head = response.head();
+        assert_eq!(head.response_kind, ResponseKind::MixedReplace);
+        assert_eq!(
+            head.header("content-type"),
+            Some("multipart/x-mixed-replace; boundary=frame")
+        );
+        assert_eq!(
+            response.next_chunk().await.unwrap(),
+            b"--frame\r\nContent-Type: image/png\r\nContent-Length: 8\r\n\r\n\x89PNG one\r\n"
+        );
+        assert_eq!(
+            response.next_chunk().await.unwrap(),
+            b"--frame\r\nContent-Type: image/png\r\nContent-Length: 8\r\n\r\n\x89PNG two\r\n"
+        );
+        assert_eq!(response.next_chunk().await, None);
+    }
+}
@@ -0,0 +1,74 @@
+//! MessagePack encoding for apps that send requests and responses over a
+//! binary IPC channel instead of JSON. Bodies are encoded as binary rather
+//! than arrays of numbers, which is where most of the savings come from.
+
+use crate::{ByteCounter, Error, LocalRequest, LocalResponse};
+
+impl LocalRequest {
+    pub fn to_msgpack(&self) -> Result<Vec<u8>, Error> {
+        Ok(rmp_serde::to_vec_named(self)?)
+    }
+
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(rmp_serde::from_slice(bytes)?)
+    }
+}
+
+impl LocalResponse {
+    pub fn to_msgpack(&self) -> Result<Vec<u8>, Error> {
+        Ok(rmp_serde::to_vec_named(self)?)
+    }
+
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(rmp_serde::from_slice(bytes)?)
+    }
+
+    /// Like [`LocalResponse::ipc_payload_size`], for the bytes
+    /// [`LocalResponse::to_msgpack`] would produce.
+    pub fn ipc_payload_size_msgpack(&self) -> usize {
+        let mut counter = ByteCounter(0);
+        rmp_serde::encode::write_named(&mut counter, self).map_or(0, |_| counter.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_request_round_trip() {
+        let mut headers = HashMap::new();
+        headers.insert("HX-Request".to_string(), "true".to_string());
+        let request = LocalRequest {
+            uri: "/todos?page=2".to_string(),
+            method: "POST".to_string(),
+            body: Some("title=milk".to_string()),
+            headers,
+        };
+
+        let encoded = request.to_msgpack().unwrap();
+        let decoded = LocalRequest::from_msgpack(&encoded).unwrap();
+        assert_eq!(decoded.uri, request.uri);
+        assert_eq!(decoded.body, request.body);
+        assert_eq!(decoded.headers, request.headers);
+        assert!(encoded.len() < serde_json::to_vec(&request).unwrap().len());
+    }
+
+    #[test]
+    fn test_binary_response_round_trip() {
+        let body: Vec<u8> = (0..=255).cycle().take(4096).collect();
+        let response = LocalResponse {
+            body: body.clone(),
+            ..Default::default()
+        }
+        .with_header("content-type", "application/octet-stream");
+
+        let encoded = response.to_msgpack().unwrap();
+        let decoded = LocalResponse::from_msgpack(&encoded).unwrap();
+        assert_eq!(decoded.body, body);
+        assert_eq!(decoded.headers, response.headers);
+        assert!(encoded.len() < serde_json::to_vec(&response).unwrap().len() / 2);
+        assert_eq!(response.ipc_payload_size_msgpack(), encoded.len());
+    }
+}
@@ -0,0 +1,350 @@
+use crate::content_disposition::{split_params, unquote};
+use crate::{Error, LocalRequest};
+use axum::body::{Body, BodyDataStream};
+use futures_util::StreamExt;
+use std::collections::BTreeMap;
+#[cfg(feature = "runtime")]
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+impl LocalRequest {
+    /// The boundary from a `multipart/*` `Content-Type` header.
+    pub fn multipart_boundary(&self) -> Option<String> {
+        multipart_boundary(self.header("content-type")?)
+    }
+}
+
+/// Extracts the boundary parameter from a `multipart/*` content type.
+pub fn multipart_boundary(content_type: &str) -> Option<String> {
+    let mut params = split_params(content_type).into_iter();
+    let mime = params.next()?.trim();
+    if !mime.to_ascii_lowercase().starts_with("multipart/") {
+        return None;
+    }
+    params.find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("boundary")
+            .then(|| unquote(value.trim()))
+            .filter(|boundary| !boundary.is_empty())
+    })
+}
+
+/// The headers of one part of a multipart body.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartHeaders {
+    /// The `name` from the part's `Content-Disposition`.
+    pub name: Option<String>,
+    /// The `filename` from the part's `Content-Disposition`.
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    /// All headers of the part, with lowercased names.
+    pub headers: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Preamble,
+    Headers,
+    Body,
+    Done,
+}
+
+/// Reads a multipart body part by part without buffering whole parts, so large
+/// uploads can be written straight to disk from a handler.
+///
+/// Each call to [`MultipartStream::next_part`] yields the next part's headers;
+/// its bytes are then read with [`MultipartStream::next_chunk`] or written out
+/// with [`MultipartStream::write_part_to`]. Unread bytes are skipped when moving
+/// to the next part.
+///
+/// A part's `filename` comes from the client and must not be used as a path
+/// as-is, since a name like `../../.bashrc` would escape the target
+/// directory. The example keeps only its final component and writes inside
+/// the temp dir.
+///
+/// ```rust,no_run
+/// use axum::{body::Body, http::Request};
+/// use tauri_axum_htmx::{multipart_boundary, Error, MultipartStream};
+///
+/// async fn upload(request: Request<Body>) -> Result<(), Error> {
+///     let content_type = request.headers()["content-type"].to_str().unwrap_or_default();
+///     let boundary = multipart_boundary(content_type).unwrap_or_default();
+///     let mut parts = MultipartStream::new(request.into_body(), &boundary);
+///     while let Some(part) = parts.next_part().await? {
+///         let name = part
+///             .filename
+///             .as_deref()
+///             .and_then(|filename| std::path::Path::new(filename).file_name());
+///         if let Some(name) = name {
+///             let path = std::env::temp_dir().join(name);
+///             let mut file = tokio::fs::File::create(path).await?;
+///             parts.write_part_to(&mut file).await?;
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct MultipartStream {
+    body: Option<BodyDataStream>,
+    delimiter: Vec<u8>,
+    buffer: Vec<u8>,
+    state: State,
+}
+
+impl MultipartStream {
+    pub fn new(body: Body, boundary: &str) -> Self {
+        MultipartStream {
+            body: Some(body.into_data_stream()),
+            delimiter: format!("\r\n--{}", boundary).into_bytes(),
+            // A leading line break lets the first boundary match the same
+            // delimiter as the ones between parts.
+            buffer: b"\r\n".to_vec(),
+            state: State::Preamble,
+        }
+    }
+
+    /// Advances to the next part and returns its headers, or `None` after the
+    /// closing boundary.
+    pub async fn next_part(&mut self) -> Result<Option<PartHeaders>, Error> {
+        while matches!(self.state, State::Preamble | State::Body) {
+            self.read_until_delimiter().await?;
+        }
+        if self.state == State::Done {
+            return Ok(None);
+        }
+
+        self.fill_to(2).await?;
+        if self.buffer.starts_with(b"--") {
+            self.state = State::Done;
+            self.buffer.clear();
+            return Ok(None);
+        }
+
+        let header_end = loop {
+            if let Some(position) = find(&self.buffer, b"\r\n\r\n") {
+                break position;
+            }
+            if !self.fill().await? {
+                return Err(Error::MultipartParseError(
+                    "body ended inside part headers".to_string(),
+                ));
+            }
+        };
+        let head: Vec<u8> = self.buffer.drain(..header_end + 4).collect();
+        let head = String::from_utf8_lossy(&head[..header_end]);
+
+        let mut part = PartHeaders::default();
+        for line in head.split("\r\n").filter(|line| !line.trim().is_empty()) {
+            let Some((name, value)) = line.split_once(':') else {
+                return Err(Error::MultipartParseError(format!(
+                    "malformed part header: {}",
+                    line
+                )));
+            };
+            part.headers
+                .insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+        if let Some(disposition) = part.headers.get("content-disposition") {
+            for param in split_params(disposition).into_iter().skip(1) {
+                if let Some((name, value)) = param.split_once('=') {
+                    match name.trim().to_ascii_lowercase().as_str() {
+                        "name" => part.name = Some(unquote(value.trim())),
+                        "filename" => part.filename = Some(unquote(value.trim())),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        part.content_type = part.headers.get("content-type").cloned();
+
+        self.state = State::Body;
+        Ok(Some(part))
+    }
+
+    /// Reads the next chunk of the current part, or `None` at the end of the part.
+    pub async fn next_chunk(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        if self.state != State::Body {
+            return Ok(None);
+        }
+        self.read_until_delimiter().await
+    }
+
+    #[cfg(feature = "runtime")]
+    /// Writes the rest of the current part to `writer`, returning the number of
+    /// bytes written.
+    pub async fn write_part_to<W>(&mut self, writer: &mut W) -> Result<u64, Error>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut written = 0;
+        while let Some(chunk) = self.next_chunk().await? {
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+        Ok(written)
+    }
+
+    /// Collects the rest of the current part into memory.
+    pub async fn read_part_to_end(&mut self) -> Result<Vec<u8>, Error> {
+        let mut data = Vec::new();
+        while let Some(chunk) = self.next_chunk().await? {
+            data.extend_from_slice(&chunk);
+        }
+        Ok(data)
+    }
+
+    /// Returns buffered bytes that cannot be part of the delimiter, moving to
+    /// [`State::Headers`] once the delimiter is reached.
+    async fn read_until_delimiter(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        loop {
+            if let Some(position) = find(&self.buffer, &self.delimiter) {
+                let chunk: Vec<u8> = self.buffer.drain(..position).collect();
+                self.buffer.drain(..self.delimiter.len());
+                self.state = State::Headers;
+                return Ok(Some(chunk).filter(|chunk| !chunk.is_empty()));
+            }
+
+            let safe = self.buffer.len().saturating_sub(self.delimiter.len() - 1);
+            if safe > 0 {
+                return Ok(Some(self.buffer.drain(..safe).collect()));
+            }
+            if !self.fill().await? {
+                return Err(Error::MultipartParseError(
+                    "body ended before the closing boundary".to_string(),
+                ));
+            }
+        }
+    }
+
+    async fn fill_to(&mut self, len: usize) -> Result<(), Error> {
+        while self.buffer.len() < len {
+            if !self.fill().await? {
+                return Err(Error::MultipartParseError(
+                    "body ended after a boundary".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    async fn fill(&mut self) -> Result<bool, Error> {
+        let Some(body) = self.body.as_mut() else {
+            return Ok(false);
+        };
+        match body.next().await {
+            Some(Ok(chunk)) => {
+                self.buffer.extend_from_slice(&chunk);
+                Ok(true)
+            }
+            Some(Err(error)) => Err(Error::MultipartParseError(error.to_string())),
+            None => {
+                self.body = None;
+                Ok(false)
+            }
+        }
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use axum::{http::Request, routing::post, Router};
+    use std::collections::HashMap;
+    use std::convert::Infallible;
+
+    const BOUNDARY: &str = "XyZ123";
+
+    fn multipart_body(file_contents: &str) -> String {
+        format!(
+            "--{b}\r\n\
+             Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+             holiday\r\n\
+             --{b}\r\n\
+             Content-Disposition: form-data; name=\"upload\"; filename=\"photo.bin\"\r\n\
+             Content-Type: application/octet-stream\r\n\r\n\
+             {file}\r\n\
+             --{b}--\r\n",
+            b = BOUNDARY,
+            file = file_contents
+        )
+    }
+
+    #[tokio::test]
+    async fn test_file_part_is_streamed_to_disk() {
+        let path = std::env::temp_dir().join(format!(
+            "tauri-axum-htmx-multipart-{}.bin",
+            std::process::id()
+        ));
+        let handler_path = path.clone();
+        let mut router = Router::new().route(
+            "/upload",
+            post(move |request: Request<Body>| async move {
+                let boundary =
+                    multipart_boundary(request.headers()["content-type"].to_str().unwrap())
+                        .unwrap();
+                let mut parts = MultipartStream::new(request.into_body(), &boundary);
+
+                let title = parts.next_part().await.unwrap().unwrap();
+                assert_eq!(title.name.as_deref(), Some("title"));
+                let title = parts.read_part_to_end().await.unwrap();
+
+                let file = parts.next_part().await.unwrap().unwrap();
+                assert_eq!(file.filename.as_deref(), Some("photo.bin"));
+                let mut output = tokio::fs::File::create(&handler_path).await.unwrap();
+                let written = parts.write_part_to(&mut output).await.unwrap();
+
+                assert!(parts.next_part().await.unwrap().is_none());
+                format!("{} {}", String::from_utf8(title).unwrap(), written)
+            }),
+        );
+
+        let contents = "0123456789".repeat(1000);
+        let mut headers = HashMap::new();
+        headers.insert(
+            "Content-Type".to_string(),
+            format!("multipart/form-data; boundary={}", BOUNDARY),
+        );
+        let request = LocalRequest {
+            uri: "/upload".to_string(),
+            method: "POST".to_string(),
+            body: Some(multipart_body(&contents)),
+            headers,
+        };
+
+        let response = request.send_to_router(&mut router).await;
+        assert_eq!(response.body_str(), Some("holiday 10000"));
+        let on_disk = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(on_disk, contents.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_delimiter_split_across_chunks() {
+        let body = multipart_body("abc");
+        let chunks: Vec<Result<Vec<u8>, Infallible>> = body
+            .as_bytes()
+            .chunks(3)
+            .map(|chunk| Ok(chunk.to_vec()))
+            .collect();
+        let mut parts = MultipartStream::new(
+            Body::from_stream(futures_util::stream::iter(chunks)),
+            BOUNDARY,
+        );
+
+        parts.next_part().await.unwrap().unwrap();
+        let upload = parts.next_part().await.unwrap().unwrap();
+        assert_eq!(
+            upload.content_type.as_deref(),
+            Some("application/octet-stream")
+        );
+        assert_eq!(parts.read_part_to_end().await.unwrap(), b"abc");
+        assert!(parts.next_part().await.unwrap().is_none());
+    }
+}
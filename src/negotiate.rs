@@ -0,0 +1,141 @@
+use crate::{LocalRequest, LocalResponse};
+
+impl LocalRequest {
+    /// The media ranges from the `Accept` header with their quality values,
+    /// in header order. Ranges with `q=0` are included so callers can tell
+    /// "not acceptable" from "not mentioned".
+    pub fn accept(&self) -> Vec<(String, f32)> {
+        let Some(header) = self.header("accept") else {
+            return Vec::new();
+        };
+        header
+            .split(',')
+            .filter_map(|range| {
+                let mut params = range.split(';');
+                let mime = params.next()?.trim().to_ascii_lowercase();
+                if mime.is_empty() {
+                    return None;
+                }
+                let quality = params
+                    .filter_map(|param| param.split_once('='))
+                    .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+                    .and_then(|(_, value)| value.trim().parse().ok())
+                    .unwrap_or(1.0);
+                Some((mime, quality))
+            })
+            .collect()
+    }
+
+    /// Whether the `Accept` header ranks JSON above HTML, e.g. for
+    /// `Accept: application/json`. `*/*` alone prefers neither.
+    pub fn prefers_json(&self) -> bool {
+        let accept = self.accept();
+        quality_for(&accept, "application/json") > quality_for(&accept, "text/html")
+    }
+}
+
+impl LocalResponse {
+    /// Picks the variant that best matches the request's `Accept` header and
+    /// builds a `200` response with its content type. The highest quality
+    /// wins; on a tie the earlier variant wins. Without an `Accept` header the
+    /// first variant is used. Answers `406` when nothing is acceptable.
+    pub fn negotiate<B: AsRef<[u8]>>(request: &LocalRequest, variants: &[(&str, B)]) -> Self {
+        let accept = request.accept();
+        let mut chosen = None;
+        if accept.is_empty() {
+            chosen = variants.first();
+        } else {
+            let mut best_quality = 0.0;
+            for variant in variants {
+                let quality = quality_for(&accept, variant.0);
+                if quality > best_quality {
+                    chosen = Some(variant);
+                    best_quality = quality;
+                }
+            }
+        }
+
+        match chosen {
+            Some((mime, body)) => LocalResponse {
+                body: body.as_ref().to_vec(),
+                ..Default::default()
+            }
+            .with_header("content-type", *mime)
+            .with_header("vary", "Accept"),
+            None => LocalResponse {
+                status_code: 406,
+                body: b"Not Acceptable".to_vec(),
+                ..Default::default()
+            }
+            .with_header("vary", "Accept"),
+        }
+    }
+}
+
+/// The quality of the most specific range matching `mime`, or `0` if none do.
+fn quality_for(accept: &[(String, f32)], mime: &str) -> f32 {
+    let mime = mime.split(';').next().unwrap_or_default().trim();
+    let (kind, _) = mime.split_once('/').unwrap_or((mime, ""));
+    let specificity = |range: &str| {
+        if range.eq_ignore_ascii_case(mime) {
+            Some(2)
+        } else if range
+            .strip_suffix("/*")
+            .is_some_and(|range_kind| range_kind.eq_ignore_ascii_case(kind))
+        {
+            Some(1)
+        } else if range == "*/*" {
+            Some(0)
+        } else {
+            None
+        }
+    };
+    accept
+        .iter()
+        .filter_map(|(range, quality)| Some((specificity(range)?, *quality)))
+        .max_by_key(|(specificity, _)| *specificity)
+        .map_or(0.0, |(_, quality)| quality)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn request_accepting(accept: &str) -> LocalRequest {
+        let mut headers = HashMap::new();
+        headers.insert("Accept".to_string(), accept.to_string());
+        LocalRequest {
+            uri: "/report".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers,
+        }
+    }
+
+    const VARIANTS: [(&str, &str); 2] = [
+        ("text/html", "<p>ok</p>"),
+        ("application/json", r#"{"ok":true}"#),
+    ];
+
+    #[test]
+    fn test_json_variant_is_chosen() {
+        let response = LocalResponse::negotiate(
+            &request_accepting("application/json, text/html;q=0.5"),
+            &VARIANTS,
+        );
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.header("content-type"), Some("application/json"));
+        assert_eq!(response.body_str(), Some(r#"{"ok":true}"#));
+
+        let response = LocalResponse::negotiate(&request_accepting("*/*"), &VARIANTS);
+        assert_eq!(response.header("content-type"), Some("text/html"));
+    }
+
+    #[test]
+    fn test_no_acceptable_variant_is_406() {
+        let response =
+            LocalResponse::negotiate(&request_accepting("image/png, text/*;q=0"), &VARIANTS);
+        assert_eq!(response.status_code, 406);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::LocalRequest;
+
+/// Decides whether a cross-cutting behaviour applies to a request, based on
+/// its path. The query string and fragment are ignored.
+///
+/// Glob patterns match whole paths: `*` matches within one segment and `**`
+/// matches across segments, so `/files/*` matches `/files/a.txt` but not
+/// `/files/docs/a.txt`, while `/files/**` matches both.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathMatcher {
+    /// Matches every path.
+    Any,
+    Exact(String),
+    Prefix(String),
+    Glob(String),
+}
+
+impl PathMatcher {
+    pub fn exact(path: impl Into<String>) -> Self {
+        PathMatcher::Exact(path.into())
+    }
+
+    pub fn prefix(prefix: impl Into<String>) -> Self {
+        PathMatcher::Prefix(prefix.into())
+    }
+
+    pub fn glob(pattern: impl Into<String>) -> Self {
+        PathMatcher::Glob(pattern.into())
+    }
+
+    pub fn matches(&self, request: &LocalRequest) -> bool {
+        self.matches_path(request.path())
+    }
+
+    pub fn matches_path(&self, path: &str) -> bool {
+        match self {
+            PathMatcher::Any => true,
+            PathMatcher::Exact(expected) => path == expected,
+            PathMatcher::Prefix(prefix) => path.starts_with(prefix.as_str()),
+            PathMatcher::Glob(pattern) => glob_matches(pattern.as_bytes(), path.as_bytes()),
+        }
+    }
+}
+
+fn glob_matches(pattern: &[u8], path: &[u8]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        [b'*', b'*', rest @ ..] => (0..=path.len()).any(|skip| glob_matches(rest, &path[skip..])),
+        [b'*', rest @ ..] => {
+            let segment_end = path.iter().position(|b| *b == b'/').unwrap_or(path.len());
+            (0..=segment_end).any(|skip| glob_matches(rest, &path[skip..]))
+        }
+        [expected, rest @ ..] => path
+            .split_first()
+            .is_some_and(|(actual, path)| actual == expected && glob_matches(rest, path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_matcher() {
+        let matcher = PathMatcher::prefix("/api/");
+        assert!(matcher.matches_path("/api/users"));
+        assert!(!matcher.matches_path("/public"));
+    }
+
+    #[test]
+    fn test_glob_matcher() {
+        let files = PathMatcher::glob("/files/*");
+        assert!(files.matches_path("/files/a.txt"));
+        assert!(!files.matches_path("/files/docs/a.txt"));
+        assert!(PathMatcher::glob("/files/**").matches_path("/files/docs/a.txt"));
+        assert!(PathMatcher::glob("/users/*/edit").matches_path("/users/42/edit"));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{LocalRequest, LocalResponse};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Answers `OPTIONS` requests for routes that exist but have no `OPTIONS` handler.
+///
+/// Axum replies to an unhandled method on a known path with `405` and an `Allow`
+/// header listing the methods it supports. When enabled on a
+/// [`Dispatcher`](crate::Dispatcher), that `405` is turned into a `204` carrying
+/// `Allow` and the configured `Access-Control-*` headers. Unknown paths still 404.
+#[derive(Clone, Debug)]
+pub struct PreflightResponder {
+    pub allow_origin: String,
+    pub allow_headers: Vec<String>,
+    pub max_age: Option<Duration>,
+}
+
+impl Default for PreflightResponder {
+    fn default() -> Self {
+        PreflightResponder {
+            allow_origin: "*".to_string(),
+            allow_headers: vec!["*".to_string()],
+            max_age: None,
+        }
+    }
+}
+
+impl PreflightResponder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_allow_origin(mut self, origin: impl Into<String>) -> Self {
+        self.allow_origin = origin.into();
+        self
+    }
+
+    pub fn with_allow_headers(mut self, headers: Vec<String>) -> Self {
+        self.allow_headers = headers;
+        self
+    }
+
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Converts the router's `405` reply to an `OPTIONS` request into a `204`.
+    /// Any other response is returned unchanged.
+    pub fn respond(&self, request: &LocalRequest, response: LocalResponse) -> LocalResponse {
+        if !request.method.eq_ignore_ascii_case("OPTIONS") || response.status_code != 405 {
+            return response;
+        }
+        let Some(allowed) = response.headers.get("allow") else {
+            return response;
+        };
+
+        let mut methods: Vec<&str> = allowed
+            .split(',')
+            .map(str::trim)
+            .filter(|method| !method.is_empty())
+            .collect();
+        if !methods.contains(&"OPTIONS") {
+            methods.push("OPTIONS");
+        }
+        let methods = methods.join(",");
+
+        let mut headers = BTreeMap::new();
+        headers.insert("allow".to_string(), methods.clone());
+        headers.insert("access-control-allow-methods".to_string(), methods);
+        headers.insert(
+            "access-control-allow-origin".to_string(),
+            self.allow_origin.clone(),
+        );
+        headers.insert(
+            "access-control-allow-headers".to_string(),
+            self.allow_headers.join(", "),
+        );
+        if let Some(max_age) = self.max_age {
+            headers.insert(
+                "access-control-max-age".to_string(),
+                max_age.as_secs().to_string(),
+            );
+        }
+
+        LocalResponse {
+            status_code: 204,
+            body: Vec::new(),
+            headers,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use crate::Dispatcher;
+    use axum::{routing::get, Router};
+    use std::collections::HashMap;
+
+    fn options_request(uri: &str) -> LocalRequest {
+        LocalRequest {
+            uri: uri.to_string(),
+            method: "OPTIONS".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_options_on_get_route_returns_204() {
+        let mut router = Router::new().route("/items", get(|| async { "items" }));
+        let dispatcher = Dispatcher::new().with_preflight(PreflightResponder::new());
+
+        let response = dispatcher
+            .send(options_request("/items"), &mut router)
+            .await;
+        assert_eq!(response.status_code, 204);
+        assert_eq!(response.headers.get("allow").unwrap(), "GET,HEAD,OPTIONS");
+        assert_eq!(
+            response.headers.get("access-control-allow-origin").unwrap(),
+            "*"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_options_on_unknown_route_is_not_answered() {
+        let mut router = Router::new().route("/items", get(|| async { "items" }));
+        let dispatcher = Dispatcher::new().with_preflight(PreflightResponder::new());
+
+        let response = dispatcher
+            .send(options_request("/missing"), &mut router)
+            .await;
+        assert_eq!(response.status_code, 404);
+    }
+}
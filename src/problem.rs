@@ -0,0 +1,97 @@
+use crate::LocalResponse;
+use serde_json::json;
+
+impl LocalResponse {
+    /// Builds an RFC 7807 `application/problem+json` error response.
+    /// `type_uri` identifies the kind of problem; `about:blank` is the
+    /// conventional value when there is nothing more specific.
+    pub fn problem(status: u16, type_uri: &str, title: &str, detail: &str) -> Self {
+        let body = json!({
+            "type": type_uri,
+            "title": title,
+            "status": status,
+            "detail": detail,
+        });
+        LocalResponse {
+            status_code: status,
+            body: body.to_string().into_bytes(),
+            ..Default::default()
+        }
+        .with_header("content-type", "application/problem+json")
+    }
+
+    /// Builds an `application/json` response from an already built `Value`,
+    /// without going through an Axum response.
+    pub fn json_value(status: u16, value: &serde_json::Value) -> Self {
+        LocalResponse {
+            status_code: status,
+            body: value.to_string().into_bytes(),
+            ..Default::default()
+        }
+        .with_header("content-type", "application/json")
+    }
+
+    /// Wraps a plain-text error body as `{"error": "..."}` for JSON clients.
+    /// Responses below `400`, and bodies that are neither text nor untyped,
+    /// are returned unchanged. An empty body is replaced by the status
+    /// reason, e.g. `Not Found`.
+    pub fn with_json_error_envelope(self) -> Self {
+        let is_text = self
+            .header("content-type")
+            .is_none_or(|content_type| content_type.trim().starts_with("text/"));
+        let message = match self.body_str() {
+            Some(message) if self.status_code >= 400 && is_text => message.trim(),
+            _ => return self,
+        };
+        let message = if message.is_empty() {
+            axum::http::StatusCode::from_u16(self.status_code)
+                .ok()
+                .and_then(|status| status.canonical_reason())
+                .unwrap_or_default()
+        } else {
+            message
+        };
+        let body = json!({ "error": message }).to_string().into_bytes();
+        let content_length = body.len().to_string();
+        LocalResponse { body, ..self }
+            .with_header("content-type", "application/json")
+            .with_header("content-length", content_length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problem_fields() {
+        let response = LocalResponse::problem(
+            404,
+            "https://example.com/problems/missing-todo",
+            "Todo not found",
+            "No todo with id 7",
+        );
+
+        assert_eq!(response.status_code, 404);
+        assert_eq!(
+            response.header("content-type"),
+            Some("application/problem+json")
+        );
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(body["type"], "https://example.com/problems/missing-todo");
+        assert_eq!(body["title"], "Todo not found");
+        assert_eq!(body["status"], 404);
+        assert_eq!(body["detail"], "No todo with id 7");
+    }
+
+    #[test]
+    fn test_json_value_response() {
+        let value = json!({"todos": [{"id": 1, "done": false}], "count": 1});
+        let response = LocalResponse::json_value(201, &value);
+
+        assert_eq!(response.status_code, 201);
+        assert_eq!(response.header("content-type"), Some("application/json"));
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(body, value);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{sanitize_header_value, LocalResponse};
+
+impl LocalResponse {
+    /// Converts a response from a remote server, so proxied API calls can be
+    /// returned through the same bridge as local ones.
+    ///
+    /// If reading the remote body fails the result is a `502 Bad Gateway`.
+    pub async fn from_reqwest(response: reqwest::Response) -> Self {
+        let status_code = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(key, value)| (key.to_string(), sanitize_header_value(value.as_bytes())))
+            .collect();
+
+        match response.bytes().await {
+            Ok(body) => LocalResponse {
+                status_code,
+                body: body.to_vec(),
+                headers,
+                ..Default::default()
+            },
+            Err(error) => LocalResponse {
+                status_code: 502,
+                body: format!("Could not read proxied response: {}", error).into(),
+                generated_by_crate: true,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reqwest_response_is_mapped() {
+        let remote = axum::http::Response::builder()
+            .status(201)
+            .header("X-Remote", "yes")
+            .body("created")
+            .unwrap();
+
+        let response = LocalResponse::from_reqwest(reqwest::Response::from(remote)).await;
+        assert_eq!(response.status_code, 201);
+        assert_eq!(response.header("x-remote"), Some("yes"));
+        assert_eq!(response.body_str(), Some("created"));
+    }
+}
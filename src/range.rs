@@ -0,0 +1,38 @@
+use crate::LocalResponse;
+
+impl LocalResponse {
+    /// Builds a `206 Partial Content` response carrying bytes `start..=end` of
+    /// a resource `full_len` bytes long. `end` is inclusive, as in
+    /// `Content-Range`; `bytes` should hold exactly that slice.
+    pub fn partial(full_len: u64, start: u64, end: u64, bytes: Vec<u8>) -> Self {
+        let content_length = bytes.len().to_string();
+        LocalResponse {
+            status_code: 206,
+            body: bytes,
+            ..Default::default()
+        }
+        .with_header(
+            "content-range",
+            format!("bytes {}-{}/{}", start, end, full_len),
+        )
+        .with_header("content-length", content_length)
+        .with_header("accept-ranges", "bytes")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_response_headers() {
+        let resource = vec![7u8; 1000];
+        let response = LocalResponse::partial(1000, 100, 199, resource[100..200].to_vec());
+
+        assert_eq!(response.status_code, 206);
+        assert_eq!(response.header("content-range"), Some("bytes 100-199/1000"));
+        assert_eq!(response.header("content-length"), Some("100"));
+        assert_eq!(response.header("accept-ranges"), Some("bytes"));
+        assert_eq!(response.body.len(), 100);
+    }
+}
@@ -0,0 +1,55 @@
+use axum::http::header::{self, HeaderValue};
+use axum::response::{IntoResponse, Response};
+
+/// A binary body with an explicit content type, for handlers producing PDFs,
+/// images and the like:
+///
+/// ```rust
+/// use tauri_axum_htmx::RawBytes;
+///
+/// async fn report() -> RawBytes {
+///     RawBytes("application/pdf".into(), b"%PDF-1.7".to_vec())
+/// }
+/// ```
+///
+/// The bytes reach [`LocalResponse::body`](crate::LocalResponse::body)
+/// unchanged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawBytes(pub String, pub Vec<u8>);
+
+impl IntoResponse for RawBytes {
+    fn into_response(self) -> Response {
+        let RawBytes(mime, bytes) = self;
+        let content_type = HeaderValue::from_str(&mime)
+            .unwrap_or(HeaderValue::from_static("application/octet-stream"));
+        ([(header::CONTENT_TYPE, content_type)], bytes).into_response()
+    }
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use crate::LocalRequest;
+    use axum::{routing::get, Router};
+    use std::collections::HashMap;
+
+    const PNG: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+    #[tokio::test]
+    async fn test_raw_bytes_round_trip() {
+        let mut router = Router::new().route(
+            "/logo.png",
+            get(|| async { RawBytes("image/png".into(), PNG.to_vec()) }),
+        );
+        let request = LocalRequest {
+            uri: "/logo.png".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let response = request.send_to_router(&mut router).await;
+        assert_eq!(response.header("content-type"), Some("image/png"));
+        assert_eq!(response.body, PNG);
+    }
+}
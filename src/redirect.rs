@@ -0,0 +1,188 @@
+use crate::asset_urls::has_scheme;
+use crate::{Error, LocalRequest, LocalResponse};
+use axum::Router;
+use std::collections::HashSet;
+
+impl LocalRequest {
+    /// Sends the request and follows redirects within the app, up to
+    /// `max_redirects` hops.
+    ///
+    /// `301`, `302` and `303` continue as a `GET` without a body; `307` and
+    /// `308` repeat the method and body. Redirects to absolute URLs leave the
+    /// app and are returned as-is. Revisiting a method and URI already seen,
+    /// or needing more than `max_redirects` hops, fails with
+    /// [`Error::RedirectLoop`].
+    ///
+    /// `HX-Trigger` headers on the redirects are merged into the returned
+    /// response's, so events fired before the redirect still reach the page.
+    pub async fn send_following_redirects(
+        self,
+        router: &mut Router,
+        max_redirects: usize,
+    ) -> Result<LocalResponse, Error> {
+        let mut request = self;
+        let mut visited = HashSet::new();
+        let mut triggers = Vec::new();
+        loop {
+            visited.insert((request.method.to_uppercase(), request.uri.clone()));
+            let response = request.clone().send_to_router(router).await;
+            let Some(location) = response.location().filter(|_| response.is_redirect()) else {
+                return Ok(merge_triggers(triggers, response));
+            };
+            if location.starts_with("//") || has_scheme(location) {
+                return Ok(merge_triggers(triggers, response));
+            }
+            triggers.extend(response.header("hx-trigger").map(str::to_string));
+
+            let uri = resolve(request.path(), location);
+            if visited.len() > max_redirects {
+                return Err(Error::RedirectLoop(uri));
+            }
+            if !matches!(response.status_code, 307 | 308) {
+                request.method = "GET".to_string();
+                request.body = None;
+                request
+                    .headers
+                    .retain(|name, _| !name.eq_ignore_ascii_case("content-type"));
+            }
+            request.uri = uri;
+            if visited.contains(&(request.method.to_uppercase(), request.uri.clone())) {
+                return Err(Error::RedirectLoop(request.uri));
+            }
+        }
+    }
+}
+
+/// Prepends `triggers` to the response's own `HX-Trigger`. Plain event lists
+/// are joined; if any value is a JSON object, the result is a JSON object
+/// where later values win.
+fn merge_triggers(mut triggers: Vec<String>, response: LocalResponse) -> LocalResponse {
+    if triggers.is_empty() {
+        return response;
+    }
+    triggers.extend(response.header("hx-trigger").map(str::to_string));
+    if !triggers
+        .iter()
+        .any(|trigger| trigger.trim_start().starts_with('{'))
+    {
+        let mut names: Vec<&str> = Vec::new();
+        for name in triggers.iter().flat_map(|trigger| trigger.split(',')) {
+            let name = name.trim();
+            if !name.is_empty() && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        return response.with_header("hx-trigger", names.join(", "));
+    }
+
+    let mut merged = serde_json::Map::new();
+    for trigger in &triggers {
+        match serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(trigger) {
+            Ok(events) => merged.extend(events),
+            Err(_) => {
+                for name in trigger.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                    merged.insert(name.to_string(), serde_json::Value::Null);
+                }
+            }
+        }
+    }
+    response.with_header("hx-trigger", serde_json::Value::Object(merged).to_string())
+}
+
+/// Resolves a `Location` value against the path it was returned for.
+fn resolve(base_path: &str, location: &str) -> String {
+    if location.starts_with('/') {
+        return location.to_string();
+    }
+    let directory = &base_path[..base_path.rfind('/').map_or(0, |slash| slash + 1)];
+    format!("{}{}", directory, location)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::Redirect;
+    use axum::routing::{get, post};
+    use std::collections::HashMap;
+
+    fn request(method: &str, uri: &str) -> LocalRequest {
+        LocalRequest {
+            uri: uri.to_string(),
+            method: method.to_string(),
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_redirect_loop_is_detected() {
+        let mut router = Router::new()
+            .route("/a", get(|| async { Redirect::to("/b") }))
+            .route("/b", get(|| async { Redirect::to("/a") }));
+
+        let result = request("GET", "/a")
+            .send_following_redirects(&mut router, 10)
+            .await;
+        assert!(matches!(result, Err(Error::RedirectLoop(uri)) if uri == "/a"));
+    }
+
+    #[tokio::test]
+    async fn test_post_redirect_get() {
+        let mut router = Router::new()
+            .route("/todos", post(|| async { Redirect::to("list") }))
+            .route("/list", get(|| async { "todos" }));
+
+        let response = request("POST", "/todos")
+            .send_following_redirects(&mut router, 10)
+            .await
+            .unwrap();
+        assert_eq!(response.body_str(), Some("todos"));
+
+        let result = request("POST", "/todos")
+            .send_following_redirects(&mut router, 0)
+            .await;
+        assert!(matches!(result, Err(Error::RedirectLoop(_))));
+    }
+
+    #[tokio::test]
+    async fn test_url_in_query_does_not_make_location_external() {
+        let mut router = Router::new()
+            .route(
+                "/account",
+                get(|| async { Redirect::to("/login?next=https://example.com/account") }),
+            )
+            .route("/login", get(|| async { "login" }));
+
+        let response = request("GET", "/account")
+            .send_following_redirects(&mut router, 10)
+            .await
+            .unwrap();
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.body_str(), Some("login"));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_triggers_are_merged() {
+        let mut router = Router::new()
+            .route(
+                "/todos",
+                post(|| async { ([("hx-trigger", "todoAdded")], Redirect::to("/list")) }),
+            )
+            .route(
+                "/list",
+                get(|| async { ([("hx-trigger", r#"{"listLoaded": {"count": 3}}"#)], "todos") }),
+            );
+
+        let response = request("POST", "/todos")
+            .send_following_redirects(&mut router, 10)
+            .await
+            .unwrap();
+        assert_eq!(response.status_code, 200);
+        let trigger: serde_json::Value =
+            serde_json::from_str(response.header("hx-trigger").unwrap()).unwrap();
+        assert_eq!(
+            trigger,
+            serde_json::json!({"todoAdded": null, "listLoaded": {"count": 3}})
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use crate::LocalResponse;
+use std::fmt::Display;
+
+impl LocalResponse {
+    /// Turns a template engine's output into a response: `200` HTML on
+    /// success, or a plain-text `500` describing the error. Behind a
+    /// [`Dispatcher`](crate::Dispatcher) with an error response configured,
+    /// the `500` is replaced by that response like any other.
+    pub fn from_render<E: Display>(result: Result<String, E>) -> Self {
+        match result {
+            Ok(html) => LocalResponse {
+                body: html.into_bytes(),
+                ..Default::default()
+            }
+            .with_header("content-type", "text/html; charset=utf-8"),
+            Err(error) => LocalResponse::internal_server_error(error)
+                .with_header("content-type", "text/plain; charset=utf-8"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rendered_html() {
+        let response = LocalResponse::from_render(Ok::<_, String>("<ul></ul>".to_string()));
+
+        assert_eq!(response.status_code, 200);
+        assert_eq!(
+            response.header("content-type"),
+            Some("text/html; charset=utf-8")
+        );
+        assert_eq!(response.body_str(), Some("<ul></ul>"));
+    }
+
+    #[test]
+    fn test_render_error() {
+        let response = LocalResponse::from_render(Err("unknown variable `todos`"));
+
+        assert_eq!(response.status_code, 500);
+        assert_eq!(
+            response.header("content-type"),
+            Some("text/plain; charset=utf-8")
+        );
+        assert!(response
+            .body_str()
+            .unwrap()
+            .contains("unknown variable `todos`"));
+    }
+}
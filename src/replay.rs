@@ -0,0 +1,64 @@
+//! Replaying captured requests against a router, e.g. to reproduce a bug report.
+//!
+//! `LocalRequest` is serializable, so the Tauri command can record requests as
+//! they arrive. The recording format is a JSON array of requests in the order
+//! they were sent, each with the same shape the webview sends over IPC:
+//!
+//! ```json
+//! [
+//!   { "uri": "/todos", "method": "GET", "headers": { "HX-Request": "true" } },
+//!   { "uri": "/todos", "method": "POST", "body": "title=milk", "headers": {} }
+//! ]
+//! ```
+//!
+//! Deserialize it with `serde_json::from_str::<Vec<LocalRequest>>` and pass it to
+//! [`replay`].
+
+use crate::{LocalRequest, LocalResponse};
+use axum::Router;
+
+/// Sends each request to `router` in order, returning the responses in the
+/// same order.
+pub async fn replay(requests: &[LocalRequest], router: &mut Router) -> Vec<LocalResponse> {
+    let mut responses = Vec::with_capacity(requests.len());
+    for request in requests {
+        responses.push(request.clone().send_to_router(router).await);
+    }
+    responses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::{get, post};
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_recorded_requests_replay() {
+        let recorded = vec![
+            LocalRequest {
+                uri: "/hello".to_string(),
+                method: "GET".to_string(),
+                body: None,
+                headers: HashMap::new(),
+            },
+            LocalRequest {
+                uri: "/echo".to_string(),
+                method: "POST".to_string(),
+                body: Some("captured".to_string()),
+                headers: HashMap::new(),
+            },
+        ];
+        let recording = serde_json::to_string(&recorded).unwrap();
+
+        let requests: Vec<LocalRequest> = serde_json::from_str(&recording).unwrap();
+        let mut router = Router::new()
+            .route("/hello", get(|| async { "hi" }))
+            .route("/echo", post(|body: String| async move { body }));
+
+        let responses = replay(&requests, &mut router).await;
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].body_str(), Some("hi"));
+        assert_eq!(responses[1].body_str(), Some("captured"));
+    }
+}
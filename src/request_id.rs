@@ -0,0 +1,112 @@
+use axum::extract::Request;
+use axum::http::header::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use std::collections::hash_map::RandomState;
+use std::fmt::Write;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// The correlation id of a request, taken from the `X-Request-Id` header sent
+/// by the JS client or generated by the [`request_id`] middleware.
+///
+/// Handlers read it with `Extension<RequestId>` to tag their logs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Generates a fresh 32 character hex id.
+    pub fn generate() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let id = (0..2u64).fold(String::with_capacity(32), |mut id, half| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u64(count);
+            hasher.write_u64(half);
+            let _ = write!(id, "{:016x}", hasher.finish());
+            id
+        });
+        RequestId(id)
+    }
+}
+
+/// Middleware that gives each request a [`RequestId`], keeping the one in
+/// `X-Request-Id` if the client sent one, and echoes it on the response.
+///
+/// ```rust
+/// use axum::{middleware, routing::get, Router};
+/// use tauri_axum_htmx::request_id;
+///
+/// let router: Router = Router::new()
+///     .route("/", get(|| async { "hello" }))
+///     .layer(middleware::from_fn(request_id));
+/// ```
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| RequestId(value.to_string()))
+        .unwrap_or_else(RequestId::generate);
+    let header_value = HeaderValue::from_str(&id.0).ok();
+    if let Some(value) = &header_value {
+        request
+            .headers_mut()
+            .insert(REQUEST_ID_HEADER, value.clone());
+    }
+    request.extensions_mut().insert(id);
+
+    let mut response = next.run(request).await;
+    if let Some(value) = header_value {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use crate::LocalRequest;
+    use axum::{middleware, routing::get, Extension, Router};
+    use std::collections::HashMap;
+
+    fn router() -> Router {
+        Router::new()
+            .route(
+                "/",
+                get(|Extension(id): Extension<RequestId>| async move { id.0 }),
+            )
+            .layer(middleware::from_fn(request_id))
+    }
+
+    fn request(headers: HashMap<String, String>) -> LocalRequest {
+        LocalRequest {
+            uri: "/".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_request_id_is_echoed() {
+        let mut headers = HashMap::new();
+        headers.insert("X-Request-Id".to_string(), "abc-123".to_string());
+
+        let response = request(headers).send_to_router(&mut router()).await;
+        assert_eq!(response.body_str(), Some("abc-123"));
+        assert_eq!(response.header("x-request-id"), Some("abc-123"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_request_id_is_generated() {
+        let response = request(HashMap::new()).send_to_router(&mut router()).await;
+        let id = response.header("x-request-id").unwrap();
+        assert_eq!(id.len(), 32);
+        assert_eq!(response.body_str(), Some(id));
+    }
+}
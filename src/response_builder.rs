@@ -0,0 +1,77 @@
+use crate::{LocalResponse, ResponseKind};
+
+impl LocalResponse {
+    /// Starts building a response, for glue code that synthesizes responses
+    /// without a router. Defaults to `200` with no headers and an empty body.
+    pub fn builder() -> LocalResponseBuilder {
+        LocalResponseBuilder::default()
+    }
+}
+
+/// Builds a [`LocalResponse`]. See [`LocalResponse::builder`].
+#[derive(Clone, Debug, Default)]
+pub struct LocalResponseBuilder {
+    response: LocalResponse,
+}
+
+impl LocalResponseBuilder {
+    pub fn status(mut self, status_code: u16) -> Self {
+        self.response.status_code = status_code;
+        self
+    }
+
+    pub fn header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.response = self.response.with_header(name, value);
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.response.body = body.into();
+        self
+    }
+
+    /// Marks the response as a server-sent event stream, setting its content
+    /// type and [`ResponseKind::Sse`].
+    pub fn sse(mut self) -> Self {
+        self.response.response_kind = ResponseKind::Sse;
+        self.header("content-type", "text/event-stream")
+            .header("cache-control", "no-cache")
+    }
+
+    pub fn build(self) -> LocalResponse {
+        self.response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SseEvent;
+
+    #[test]
+    fn test_build_normal_response() {
+        let response = LocalResponse::builder()
+            .status(201)
+            .header("Content-Type", "text/html")
+            .body("<p>created</p>")
+            .build();
+
+        assert_eq!(response.status_code, 201);
+        assert_eq!(response.header("content-type"), Some("text/html"));
+        assert_eq!(response.body_str(), Some("<p>created</p>"));
+        assert_eq!(response.response_kind, ResponseKind::Normal);
+        assert_eq!(LocalResponse::builder().build().status_code, 200);
+    }
+
+    #[test]
+    fn test_build_sse_response() {
+        let response = LocalResponse::builder()
+            .sse()
+            .body(SseEvent::new("tick").to_frame())
+            .build();
+
+        assert_eq!(response.response_kind, ResponseKind::Sse);
+        assert_eq!(response.header("content-type"), Some("text/event-stream"));
+        assert_eq!(response.body_str(), Some("data: tick\n\n"));
+    }
+}
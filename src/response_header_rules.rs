@@ -0,0 +1,82 @@
+use crate::{LocalRequest, LocalResponse, PathMatcher};
+
+/// Adds headers to responses for matching paths, e.g. `Cache-Control` for
+/// everything under `/static/`, without touching the handlers.
+///
+/// A header the handler already set wins. When several rules set the same
+/// header, the first one added wins.
+#[derive(Clone, Debug, Default)]
+pub struct ResponseHeaderRules {
+    rules: Vec<(PathMatcher, String, String)>,
+}
+
+impl ResponseHeaderRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_header(
+        mut self,
+        matcher: PathMatcher,
+        name: &str,
+        value: impl Into<String>,
+    ) -> Self {
+        self.rules
+            .push((matcher, name.to_ascii_lowercase(), value.into()));
+        self
+    }
+
+    pub fn apply(&self, request: &LocalRequest, mut response: LocalResponse) -> LocalResponse {
+        for (matcher, name, value) in &self.rules {
+            if response.header(name).is_none() && matcher.matches(request) {
+                response.headers.insert(name.clone(), value.clone());
+            }
+        }
+        response
+    }
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use crate::Dispatcher;
+    use axum::{routing::get, Router};
+    use std::collections::HashMap;
+
+    fn request(uri: &str) -> LocalRequest {
+        LocalRequest {
+            uri: uri.to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_static_responses_get_cache_control() {
+        let mut router = Router::new()
+            .route("/static/app.css", get(|| async { "body {}" }))
+            .route("/api/items", get(|| async { "[]" }))
+            .route(
+                "/static/live.css",
+                get(|| async { ([("cache-control", "no-store")], "body {}") }),
+            );
+        let dispatcher =
+            Dispatcher::new().with_response_header_rules(ResponseHeaderRules::new().with_header(
+                PathMatcher::prefix("/static/"),
+                "Cache-Control",
+                "max-age=3600",
+            ));
+
+        let response = dispatcher
+            .send(request("/static/app.css"), &mut router)
+            .await;
+        assert_eq!(response.header("cache-control"), Some("max-age=3600"));
+        let response = dispatcher.send(request("/api/items"), &mut router).await;
+        assert_eq!(response.header("cache-control"), None);
+        let response = dispatcher
+            .send(request("/static/live.css"), &mut router)
+            .await;
+        assert_eq!(response.header("cache-control"), Some("no-store"));
+    }
+}
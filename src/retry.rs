@@ -0,0 +1,140 @@
+use crate::{LocalRequest, LocalResponse};
+use axum::http::{Method, StatusCode};
+use std::time::Duration;
+
+/// Controls how a [`Dispatcher`](crate::Dispatcher) retries requests that fail
+/// with a transient status.
+///
+/// By default only idempotent methods (`GET` and `HEAD`) are retried, and only
+/// when the router answers with `503 Service Unavailable`. The delay between
+/// attempts doubles each time, starting from `base_delay`.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub retryable_statuses: Vec<StatusCode>,
+    pub retryable_methods: Vec<Method>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(50),
+            retryable_statuses: vec![StatusCode::SERVICE_UNAVAILABLE],
+            retryable_methods: vec![Method::GET, Method::HEAD],
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        RetryPolicy {
+            max_attempts,
+            base_delay,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_retryable_statuses(mut self, statuses: Vec<StatusCode>) -> Self {
+        self.retryable_statuses = statuses;
+        self
+    }
+
+    pub fn with_retryable_methods(mut self, methods: Vec<Method>) -> Self {
+        self.retryable_methods = methods;
+        self
+    }
+
+    /// Whether `response`, returned on attempt number `attempt` (starting at 1),
+    /// should be retried.
+    pub fn should_retry(
+        &self,
+        request: &LocalRequest,
+        response: &LocalResponse,
+        attempt: u32,
+    ) -> bool {
+        attempt < self.max_attempts
+            && self
+                .retryable_statuses
+                .iter()
+                .any(|status| status.as_u16() == response.status_code)
+            && self
+                .retryable_methods
+                .iter()
+                .any(|method| method.as_str().eq_ignore_ascii_case(&request.method))
+    }
+
+    /// The delay to wait after a failed attempt number `attempt` (starting at 1).
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use crate::Dispatcher;
+    use axum::{routing::any, Router};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn flaky_router(calls: Arc<AtomicUsize>) -> Router {
+        Router::new().route(
+            "/flaky",
+            any(move || {
+                let calls = calls.clone();
+                async move {
+                    if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                        (StatusCode::SERVICE_UNAVAILABLE, "busy")
+                    } else {
+                        (StatusCode::OK, "done")
+                    }
+                }
+            }),
+        )
+    }
+
+    fn request(method: &str) -> LocalRequest {
+        LocalRequest {
+            uri: "/flaky".to_string(),
+            method: method.to_string(),
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_is_retried_until_success() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut router = flaky_router(calls.clone());
+        let dispatcher =
+            Dispatcher::new().with_retry_policy(RetryPolicy::new(3, Duration::from_millis(1)));
+
+        let response = dispatcher.send(request("GET"), &mut router).await;
+        assert_eq!(response.status_code, 200);
+        assert_eq!(String::from_utf8(response.body).unwrap(), "done");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_post_is_not_retried() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut router = flaky_router(calls.clone());
+        let dispatcher =
+            Dispatcher::new().with_retry_policy(RetryPolicy::new(3, Duration::from_millis(1)));
+
+        let response = dispatcher.send(request("POST"), &mut router).await;
+        assert_eq!(response.status_code, 503);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_delay_doubles_per_attempt() {
+        let policy = RetryPolicy::new(4, Duration::from_millis(10));
+        assert_eq!(policy.delay_for(1), Duration::from_millis(10));
+        assert_eq!(policy.delay_for(3), Duration::from_millis(40));
+    }
+}
@@ -0,0 +1,230 @@
+#[cfg(feature = "runtime")]
+use crate::{Error, LocalRequest, LocalResponse};
+use axum::handler::Handler;
+use axum::http::Method;
+use axum::routing::{self, MethodRouter};
+use axum::Router;
+use serde_json::json;
+
+/// A route registered through a [`RouteTable`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouteEntry {
+    pub path: String,
+    /// The methods handled at `path`. Empty when added through
+    /// [`RouteTable::route`], where the methods are not known.
+    pub methods: Vec<Method>,
+}
+
+/// Builds a [`Router`] while recording the routes added to it.
+///
+/// Axum does not expose the routes of a `Router`, so registering them through
+/// this wrapper is what makes them available for debug screens, and lets
+/// [`RouteTable::send`] tell an unknown path apart from a handler that answers
+/// `404` itself.
+#[derive(Clone, Debug, Default)]
+pub struct RouteTable {
+    router: Router,
+    routes: Vec<RouteEntry>,
+}
+
+impl RouteTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn route(mut self, path: &str, method_router: MethodRouter) -> Self {
+        self.router = self.router.route(path, method_router);
+        self.record(path, None);
+        self
+    }
+
+    pub fn get<H, T>(self, path: &str, handler: H) -> Self
+    where
+        H: Handler<T, ()>,
+        T: 'static,
+    {
+        self.method_route(path, Method::GET, routing::get(handler))
+    }
+
+    pub fn post<H, T>(self, path: &str, handler: H) -> Self
+    where
+        H: Handler<T, ()>,
+        T: 'static,
+    {
+        self.method_route(path, Method::POST, routing::post(handler))
+    }
+
+    pub fn put<H, T>(self, path: &str, handler: H) -> Self
+    where
+        H: Handler<T, ()>,
+        T: 'static,
+    {
+        self.method_route(path, Method::PUT, routing::put(handler))
+    }
+
+    pub fn patch<H, T>(self, path: &str, handler: H) -> Self
+    where
+        H: Handler<T, ()>,
+        T: 'static,
+    {
+        self.method_route(path, Method::PATCH, routing::patch(handler))
+    }
+
+    pub fn delete<H, T>(self, path: &str, handler: H) -> Self
+    where
+        H: Handler<T, ()>,
+        T: 'static,
+    {
+        self.method_route(path, Method::DELETE, routing::delete(handler))
+    }
+
+    fn method_route(mut self, path: &str, method: Method, method_router: MethodRouter) -> Self {
+        self.router = self.router.route(path, method_router);
+        self.record(path, Some(method));
+        self
+    }
+
+    fn record(&mut self, path: &str, method: Option<Method>) {
+        let index = match self.routes.iter().position(|entry| entry.path == path) {
+            Some(index) => index,
+            None => {
+                self.routes.push(RouteEntry {
+                    path: path.to_string(),
+                    methods: Vec::new(),
+                });
+                self.routes.len() - 1
+            }
+        };
+        self.routes[index].methods.extend(method);
+    }
+
+    pub fn routes(&self) -> &[RouteEntry] {
+        &self.routes
+    }
+
+    /// The recorded routes as JSON, e.g.
+    /// `[{"path": "/users/{id}", "methods": ["GET", "POST"]}]`, for the JS
+    /// client to build a navigation menu from.
+    pub fn to_json(&self) -> serde_json::Value {
+        self.routes
+            .iter()
+            .map(|entry| {
+                json!({
+                    "path": entry.path,
+                    "methods": entry.methods.iter().map(Method::as_str).collect::<Vec<_>>(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Finds the registered route matching `path`, understanding `{param}` and
+    /// `{*rest}` segments.
+    pub fn find(&self, path: &str) -> Option<&RouteEntry> {
+        self.routes
+            .iter()
+            .find(|entry| path_matches(&entry.path, path))
+    }
+
+    pub fn router(&self) -> &Router {
+        &self.router
+    }
+
+    pub fn into_router(self) -> Router {
+        self.router
+    }
+
+    #[cfg(feature = "runtime")]
+    /// Sends the request to the router, or fails with [`Error::NoMatchingRoute`]
+    /// without calling it when no registered route matches the path.
+    pub async fn send(&self, request: LocalRequest) -> Result<LocalResponse, Error> {
+        if self.find(request.path()).is_none() {
+            return Err(Error::NoMatchingRoute(request.path().to_string()));
+        }
+        Ok(request.send_to_router_shared(&self.router).await)
+    }
+}
+
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let mut pattern_segments = pattern.trim_start_matches('/').split('/');
+    let mut path_segments = path.trim_start_matches('/').split('/');
+    loop {
+        match (pattern_segments.next(), path_segments.next()) {
+            (Some(segment), _) if segment.starts_with("{*") => return true,
+            (Some(segment), Some(actual)) => {
+                let is_param = segment.starts_with('{') && segment.ends_with('}');
+                if !(is_param && !actual.is_empty() || segment == actual) {
+                    return false;
+                }
+            }
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use std::collections::HashMap;
+
+    fn request(uri: &str) -> LocalRequest {
+        LocalRequest {
+            uri: uri.to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    fn table() -> RouteTable {
+        RouteTable::new()
+            .get("/users/{id}", || async { "user" })
+            .post("/users/{id}", || async { "updated" })
+            .get("/gone", || async { StatusCode::NOT_FOUND })
+    }
+
+    #[tokio::test]
+    async fn test_unregistered_path_is_reported_as_no_match() {
+        let table = table();
+
+        let response = table.send(request("/users/7")).await.unwrap();
+        assert_eq!(response.body_str(), Some("user"));
+
+        let handler_404 = table.send(request("/gone")).await.unwrap();
+        assert_eq!(handler_404.status_code, 404);
+
+        let error = table.send(request("/missing")).await.unwrap_err();
+        assert!(matches!(error, Error::NoMatchingRoute(path) if path == "/missing"));
+    }
+
+    #[test]
+    fn test_routes_are_recorded_with_methods() {
+        let table = table();
+        assert_eq!(table.len(), 2);
+        assert_eq!(
+            table.find("/users/7").unwrap().methods,
+            vec![Method::GET, Method::POST]
+        );
+        assert!(table.find("/users").is_none());
+    }
+
+    #[test]
+    fn test_route_listing_json() {
+        assert_eq!(
+            table().to_json(),
+            json!([
+                {"path": "/users/{id}", "methods": ["GET", "POST"]},
+                {"path": "/gone", "methods": ["GET"]},
+            ])
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{LocalResponse, ResponseKind};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A single server-sent event, serialized in the `text/event-stream` format.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    pub id: Option<String>,
+    pub event: Option<String>,
+    pub data: String,
+    pub retry: Option<u64>,
+}
+
+impl SseEvent {
+    /// Marks `data` that was base64-encoded by [`SseEvent::from_bytes`].
+    pub const BASE64_PREFIX: &'static str = "base64:";
+
+    pub fn new(data: impl Into<String>) -> Self {
+        SseEvent {
+            data: data.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Builds an event from raw bytes.
+    ///
+    /// UTF-8 data is sent as-is. Anything else is base64-encoded and prefixed
+    /// with [`SseEvent::BASE64_PREFIX`], so a client can recover the bytes by
+    /// stripping the prefix and decoding the rest. UTF-8 data that happens to
+    /// start with the prefix is encoded too, keeping the convention unambiguous.
+    /// [`SseEvent::data_bytes`] reverses the encoding.
+    pub fn from_bytes(data: &[u8]) -> Self {
+        match std::str::from_utf8(data) {
+            Ok(text) if !text.starts_with(Self::BASE64_PREFIX) => SseEvent::new(text),
+            _ => SseEvent::new(format!(
+                "{}{}",
+                Self::BASE64_PREFIX,
+                base64::engine::general_purpose::STANDARD.encode(data)
+            )),
+        }
+    }
+
+    /// The data as bytes, decoding it if it follows the base64 convention.
+    pub fn data_bytes(&self) -> Vec<u8> {
+        self.data
+            .strip_prefix(Self::BASE64_PREFIX)
+            .and_then(|encoded| {
+                base64::engine::general_purpose::STANDARD
+                    .decode(encoded)
+                    .ok()
+            })
+            .unwrap_or_else(|| self.data.as_bytes().to_vec())
+    }
+
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn with_event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    pub fn with_retry(mut self, retry_ms: u64) -> Self {
+        self.retry = Some(retry_ms);
+        self
+    }
+
+    /// Serializes the event as a frame terminated by a blank line. Multi-line
+    /// data is split across several `data:` lines.
+    pub fn to_frame(&self) -> String {
+        let mut frame = String::new();
+        if let Some(id) = &self.id {
+            frame.push_str(&format!("id: {}\n", id));
+        }
+        if let Some(event) = &self.event {
+            frame.push_str(&format!("event: {}\n", event));
+        }
+        if let Some(retry) = self.retry {
+            frame.push_str(&format!("retry: {}\n", retry));
+        }
+        for line in self.data.split('\n') {
+            frame.push_str(&format!("data: {}\n", line));
+        }
+        frame.push('\n');
+        frame
+    }
+
+    /// Parses a raw `text/event-stream` body, e.g. a collected SSE response in
+    /// a test, into its events.
+    ///
+    /// Frames are separated by blank lines and `data:` lines within a frame
+    /// are joined with `\n`. Comment lines and unknown fields are ignored, and
+    /// frames holding nothing else are skipped. A final frame without a
+    /// trailing blank line is still returned.
+    pub fn parse_stream(body: &[u8]) -> Vec<SseEvent> {
+        let body = String::from_utf8_lossy(body);
+        let mut events = Vec::new();
+        let mut event = SseEvent::default();
+        let mut data_lines: Vec<&str> = Vec::new();
+        let mut has_fields = false;
+        for line in body
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        {
+            if line.is_empty() {
+                if has_fields {
+                    event.data = data_lines.join("\n");
+                    events.push(std::mem::take(&mut event));
+                    data_lines.clear();
+                    has_fields = false;
+                }
+                continue;
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "id" => event.id = Some(value.to_string()),
+                "event" => event.event = Some(value.to_string()),
+                "data" => data_lines.push(value),
+                "retry" => event.retry = value.parse().ok(),
+                _ => continue,
+            }
+            has_fields = true;
+        }
+        if has_fields {
+            event.data = data_lines.join("\n");
+            events.push(event);
+        }
+        events
+    }
+}
+
+impl LocalResponse {
+    /// Builds a `200` response carrying an already serialized `text/event-stream` body.
+    pub fn sse(body: impl Into<String>) -> Self {
+        let mut headers = BTreeMap::new();
+        headers.insert("content-type".to_string(), "text/event-stream".to_string());
+        headers.insert("cache-control".to_string(), "no-cache".to_string());
+
+        LocalResponse {
+            status_code: 200,
+            body: body.into().into_bytes(),
+            headers,
+            response_kind: ResponseKind::Sse,
+            ..Default::default()
+        }
+    }
+
+    /// Serializes each event in order and builds an SSE response from them.
+    pub fn sse_from_events(events: impl IntoIterator<Item = SseEvent>) -> Self {
+        let body: String = events.into_iter().map(|event| event.to_frame()).collect();
+        LocalResponse::sse(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_line_data_is_split() {
+        let event = SseEvent::new("first\nsecond").with_event("update");
+        assert_eq!(
+            event.to_frame(),
+            "event: update\ndata: first\ndata: second\n\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_stream() {
+        let body = b": ok\n\nid: 1\nevent: update\ndata: first\ndata: second\n\ndata: done\n\n";
+
+        assert_eq!(
+            SseEvent::parse_stream(body),
+            vec![
+                SseEvent::new("first\nsecond")
+                    .with_id("1")
+                    .with_event("update"),
+                SseEvent::new("done"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_non_utf8_bytes_are_base64_encoded() {
+        let bytes = [0xff, 0x00, 0x80];
+        let event = SseEvent::from_bytes(&bytes);
+        assert_eq!(event.to_frame(), "data: base64:/wCA\n\n");
+        assert_eq!(event.data_bytes(), bytes);
+
+        let text = SseEvent::from_bytes(b"progress 50%");
+        assert_eq!(text.to_frame(), "data: progress 50%\n\n");
+        assert_eq!(text.data_bytes(), b"progress 50%");
+    }
+
+    #[test]
+    fn test_sse_from_events() {
+        let response = LocalResponse::sse_from_events(vec![
+            SseEvent::new("one").with_id("1"),
+            SseEvent::new("two").with_event("tick"),
+            SseEvent::new("three").with_retry(1000),
+        ]);
+
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.header("content-type"), Some("text/event-stream"));
+        assert_eq!(response.header("cache-control"), Some("no-cache"));
+
+        let body = response.body_str().unwrap();
+        assert!(body.contains("id: 1\ndata: one\n\n"));
+        assert!(body.contains("event: tick\ndata: two\n\n"));
+        assert!(body.contains("retry: 1000\ndata: three\n\n"));
+    }
+
+    #[cfg(feature = "runtime")]
+    #[tokio::test]
+    async fn test_sse_response_kind_is_serialized() {
+        use crate::{LocalRequest, SseStream};
+        use axum::{routing::get, Router};
+        use std::collections::HashMap;
+
+        let mut router = Router::new().route(
+            "/events",
+            get(|| async { SseStream::new(futures_util::stream::iter(vec![SseEvent::new("hi")])) }),
+        );
+        let request = LocalRequest {
+            uri: "/events".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let response = request.send_to_router(&mut router).await;
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["response_kind"], "Sse");
+        assert_eq!(LocalResponse::default().response_kind, ResponseKind::Normal);
+    }
+}
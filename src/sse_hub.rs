@@ -0,0 +1,164 @@
+use crate::{SseEvent, SseStream};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// Tracks open [`SseStream`]s by channel id so events can be pushed to them from
+/// outside a request, e.g. from a background task.
+///
+/// A handler opens a stream with [`SseHub::subscribe`] and returns it; anything
+/// holding a clone of the hub can then [`send`](SseHub::send) to that channel.
+/// Streams whose clients have gone away are pruned on the next send.
+///
+/// Clones share the same set of streams.
+#[derive(Clone, Debug, Default)]
+pub struct SseHub {
+    channels: Arc<Mutex<HashMap<String, Vec<mpsc::UnboundedSender<SseEvent>>>>>,
+}
+
+impl SseHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens a new stream registered under `channel`.
+    pub fn subscribe(&self, channel: impl Into<String>) -> SseStream {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.channels
+            .lock()
+            .unwrap()
+            .entry(channel.into())
+            .or_default()
+            .push(sender);
+        SseStream::from_receiver(receiver)
+    }
+
+    /// Pushes `event` to every open stream on `channel`, returning how many
+    /// streams received it.
+    pub fn send(&self, channel: &str, event: SseEvent) -> usize {
+        let mut channels = self.channels.lock().unwrap();
+        let Some(senders) = channels.get_mut(channel) else {
+            return 0;
+        };
+        senders.retain(|sender| sender.send(event.clone()).is_ok());
+        let delivered = senders.len();
+        if senders.is_empty() {
+            channels.remove(channel);
+        }
+        delivered
+    }
+
+    /// Pushes `event` to every open stream on every channel.
+    pub fn broadcast(&self, event: SseEvent) -> usize {
+        let channels: Vec<String> = self.channels.lock().unwrap().keys().cloned().collect();
+        channels
+            .iter()
+            .map(|channel| self.send(channel, event.clone()))
+            .sum()
+    }
+
+    /// Ends every stream on `channel`, returning how many were open. Each
+    /// stream finishes once it has delivered the events already sent to it.
+    pub fn close(&self, channel: &str) -> usize {
+        self.channels
+            .lock()
+            .unwrap()
+            .remove(channel)
+            .map_or(0, |senders| senders.len())
+    }
+
+    /// Ends every stream on every channel, e.g. when the page holding them is
+    /// left. Returns how many were open.
+    pub fn close_all(&self) -> usize {
+        std::mem::take(&mut *self.channels.lock().unwrap())
+            .into_values()
+            .map(|senders| senders.len())
+            .sum()
+    }
+
+    pub fn subscriber_count(&self, channel: &str) -> usize {
+        self.channels
+            .lock()
+            .unwrap()
+            .get(channel)
+            .map_or(0, |senders| {
+                senders.iter().filter(|s| !s.is_closed()).count()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LocalRequest;
+    use axum::{routing::get, Router};
+
+    #[tokio::test]
+    async fn test_event_pushed_through_hub_reaches_client() {
+        let hub = SseHub::new();
+        let handler_hub = hub.clone();
+        let mut router = Router::new().route(
+            "/events",
+            get(move || async move { handler_hub.subscribe("news") }),
+        );
+        let request = LocalRequest {
+            uri: "/events".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let mut response = request.send_to_router_streaming(&mut router).await;
+        assert_eq!(
+            response.head().header("content-type"),
+            Some("text/event-stream")
+        );
+        assert_eq!(hub.subscriber_count("news"), 1);
+        assert_eq!(response.next_chunk().await, Some(b": ok\n\n".to_vec()));
+        assert_eq!(
+            response.next_chunk().await,
+            Some(b"retry: 3000\n\n".to_vec())
+        );
+
+        assert_eq!(hub.send("news", SseEvent::new("hello").with_id("1")), 1);
+        assert_eq!(
+            response.next_chunk().await,
+            Some(b"id: 1\ndata: hello\n\n".to_vec())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_reaches_every_channel() {
+        let hub = SseHub::new();
+        let mut first = hub.subscribe("a");
+        let mut second = hub.subscribe("b");
+        for stream in [&mut first, &mut second] {
+            assert_eq!(stream.next_frame().await.unwrap(), ": ok\n\n");
+            stream.next_frame().await;
+        }
+
+        assert_eq!(hub.broadcast(SseEvent::new("all")), 2);
+        assert_eq!(first.next_frame().await.unwrap(), "data: all\n\n");
+        assert_eq!(second.next_frame().await.unwrap(), "data: all\n\n");
+        assert_eq!(hub.send("missing", SseEvent::new("none")), 0);
+    }
+
+    #[tokio::test]
+    async fn test_close_all_ends_streams() {
+        let hub = SseHub::new();
+        let mut first = hub.subscribe("a");
+        let mut second = hub.subscribe("b");
+        let mut third = hub.subscribe("b");
+        for stream in [&mut first, &mut second, &mut third] {
+            stream.next_frame().await;
+            stream.next_frame().await;
+        }
+
+        assert_eq!(hub.close("b"), 2);
+        assert_eq!(third.next_frame().await, None);
+        assert_eq!(hub.close_all(), 1);
+        let (first, second) = tokio::join!(first.next_frame(), second.next_frame());
+        assert_eq!((first, second), (None, None));
+        assert_eq!(hub.subscriber_count("a"), 0);
+    }
+}
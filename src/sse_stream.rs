@@ -0,0 +1,513 @@
+use crate::{LocalResponse, SseEvent, StreamingResponse};
+use axum::body::Body;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use futures_util::stream::{self, BoxStream, Stream, StreamExt};
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+
+/// Reconnection and keep-alive settings for an [`SseStream`].
+///
+/// `retry_ms` is sent before any event and tells the client how long to wait
+/// before reconnecting after the stream drops. Clients are expected to treat
+/// it as the base delay and back off exponentially on repeated failures.
+/// With `open_comment`, an `: ok` comment is sent before anything else,
+/// because some webviews don't fire the `EventSource` `open` event until the
+/// first byte arrives. With a `keep_alive_interval`, a comment frame is sent
+/// whenever the stream has been idle that long, so the connection is not
+/// mistaken for a dead one.
+///
+/// Every stream starts with the opening frames of `SseConfig::default()`.
+/// Keep-alives are only sent once a config is set with
+/// [`SseStream::with_config`], which also replaces the opening frames, e.g. to
+/// turn the `: ok` comment off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SseConfig {
+    pub retry_ms: u64,
+    pub keep_alive_interval: Option<Duration>,
+    pub open_comment: bool,
+}
+
+impl Default for SseConfig {
+    fn default() -> Self {
+        SseConfig {
+            retry_ms: 3000,
+            keep_alive_interval: Some(Duration::from_secs(15)),
+            open_comment: true,
+        }
+    }
+}
+
+/// The frames sent before any event: the optional `: ok` comment, then the
+/// `retry:` frame.
+fn preamble(config: &SseConfig) -> VecDeque<String> {
+    let mut frames = VecDeque::new();
+    if config.open_comment {
+        frames.push_back(": ok\n\n".to_string());
+    }
+    frames.push_back(format!("retry: {}\n\n", config.retry_ms));
+    frames
+}
+
+const KEEP_ALIVE_FRAME: &str = ": keep-alive\n\n";
+
+/// The event name of the frame sent by [`SseStream::with_completion_trigger`].
+pub const HX_TRIGGER_EVENT: &str = "hx-trigger";
+
+/// A live stream of server-sent events that can be returned from a handler.
+///
+/// The response uses `text/event-stream` and stays open until the underlying
+/// event source ends. Read it through the bridge with
+/// [`LocalRequest::send_to_router_streaming`](crate::LocalRequest::send_to_router_streaming).
+pub struct SseStream {
+    items: BoxStream<'static, Item>,
+    config: Option<SseConfig>,
+    /// Frames sent before any event. Every stream starts with those of
+    /// [`SseConfig::default`], replaced by [`SseStream::with_config`].
+    preamble: VecDeque<String>,
+    acks: Option<SseAckTracker>,
+}
+
+enum Item {
+    Event(SseEvent),
+    /// Sent as an SSE comment, which clients ignore.
+    Comment(String),
+}
+
+/// Events sent with an id that the client has not acknowledged yet.
+#[derive(Debug)]
+struct AckState {
+    receiver: mpsc::UnboundedReceiver<String>,
+    unacked: VecDeque<SseEvent>,
+    last_acked_id: Option<String>,
+}
+
+/// Feeds event ids acknowledged by the client back to an [`SseStream`].
+///
+/// The Tauri layer calls [`SseAckSender::ack`] with the id of each event the
+/// JS client confirms. Acknowledging an id also acknowledges every event sent
+/// before it.
+#[derive(Clone, Debug)]
+pub struct SseAckSender {
+    sender: mpsc::UnboundedSender<String>,
+}
+
+impl SseAckSender {
+    /// Returns `false` once the stream and its tracker have been dropped.
+    pub fn ack(&self, id: impl Into<String>) -> bool {
+        self.sender.send(id.into()).is_ok()
+    }
+}
+
+/// The acknowledgement state of an [`SseStream`], shared with the stream so it
+/// can still be inspected after the stream has been returned from a handler.
+///
+/// Acknowledged events are dropped as the stream sends further frames, and
+/// whenever the tracker is read.
+#[derive(Clone, Debug)]
+pub struct SseAckTracker {
+    state: Arc<Mutex<AckState>>,
+}
+
+impl SseAckTracker {
+    /// The most recently acknowledged event id.
+    pub fn last_acked_id(&self) -> Option<String> {
+        let mut state = self.state.lock().unwrap();
+        state.process_acks();
+        state.last_acked_id.clone()
+    }
+
+    /// Events sent with an id but not acknowledged yet, oldest first.
+    pub fn unacked_events(&self) -> Vec<SseEvent> {
+        let mut state = self.state.lock().unwrap();
+        state.process_acks();
+        state.unacked.iter().cloned().collect()
+    }
+
+    fn record(&self, event: &SseEvent) {
+        let mut state = self.state.lock().unwrap();
+        state.process_acks();
+        state.unacked.push_back(event.clone());
+    }
+}
+
+impl AckState {
+    /// Drops buffered events up to and including each acknowledged id.
+    /// Unknown ids are ignored.
+    fn process_acks(&mut self) {
+        while let Ok(id) = self.receiver.try_recv() {
+            let position = self
+                .unacked
+                .iter()
+                .position(|event| event.id.as_deref() == Some(id.as_str()));
+            if let Some(position) = position {
+                self.unacked.drain(..=position);
+                self.last_acked_id = Some(id);
+            }
+        }
+    }
+}
+
+impl SseStream {
+    pub fn new(events: impl Stream<Item = SseEvent> + Send + 'static) -> Self {
+        SseStream::from_items(events.map(Item::Event))
+    }
+
+    fn from_items(items: impl Stream<Item = Item> + Send + 'static) -> Self {
+        SseStream {
+            items: items.boxed(),
+            config: None,
+            preamble: preamble(&SseConfig::default()),
+            acks: None,
+        }
+    }
+
+    /// Keeps every event sent with an id until the client acknowledges it
+    /// through the returned sender, so undelivered events can be resent. The
+    /// returned tracker reads the acknowledgement state.
+    pub fn with_acks(mut self) -> (Self, SseAckSender, SseAckTracker) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let tracker = SseAckTracker {
+            state: Arc::new(Mutex::new(AckState {
+                receiver,
+                unacked: VecDeque::new(),
+                last_acked_id: None,
+            })),
+        };
+        self.acks = Some(tracker.clone());
+        (self, SseAckSender { sender }, tracker)
+    }
+
+    /// Sends a final `hx-trigger` event once the stream ends, whose data is
+    /// `trigger`. The JS client dispatches it on the element like an
+    /// `HX-Trigger` response header, e.g. to refresh a list after an import
+    /// finishes streaming.
+    pub fn with_completion_trigger(mut self, trigger: impl Into<String>) -> Self {
+        let event = SseEvent::new(trigger).with_event(HX_TRIGGER_EVENT);
+        let items = std::mem::replace(&mut self.items, stream::empty().boxed());
+        self.items = items
+            .chain(stream::once(async move { Item::Event(event) }))
+            .boxed();
+        self
+    }
+
+    /// Replaces the default opening frames with the configured ones and sends
+    /// keep-alives while idle. See [`SseConfig`].
+    pub fn with_config(mut self, config: SseConfig) -> Self {
+        self.preamble = preamble(&config);
+        self.config = Some(config);
+        self
+    }
+
+    /// Streams events sent on `receiver` until every sender has been dropped.
+    pub fn from_receiver(receiver: mpsc::UnboundedReceiver<SseEvent>) -> Self {
+        SseStream::new(stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|event| (event, receiver))
+        }))
+    }
+
+    /// Streams events from a broadcast channel, so one sender can fan out to
+    /// many clients. If this receiver falls behind and misses events, a
+    /// comment saying how many were skipped is sent and streaming continues.
+    pub fn from_broadcast(receiver: broadcast::Receiver<SseEvent>) -> Self {
+        let items = stream::unfold(receiver, |mut receiver| async move {
+            match receiver.recv().await {
+                Ok(event) => Some((Item::Event(event), receiver)),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => Some((
+                    Item::Comment(format!("lagged, skipped {} events", skipped)),
+                    receiver,
+                )),
+                Err(broadcast::error::RecvError::Closed) => None,
+            }
+        });
+        SseStream::from_items(items)
+    }
+
+    /// Waits for the next serialized frame, or `None` once the stream has ended.
+    pub async fn next_frame(&mut self) -> Option<String> {
+        if let Some(frame) = self.preamble.pop_front() {
+            return Some(frame);
+        }
+        let keep_alive_interval = self.config.and_then(|config| config.keep_alive_interval);
+        let item = match keep_alive_interval {
+            Some(interval) => match tokio::time::timeout(interval, self.items.next()).await {
+                Ok(item) => item,
+                Err(_) => return Some(KEEP_ALIVE_FRAME.to_string()),
+            },
+            None => self.items.next().await,
+        }?;
+        let event = match item {
+            Item::Event(event) => event,
+            Item::Comment(comment) => return Some(format!(": {}\n\n", comment)),
+        };
+
+        if let Some(acks) = self.acks.as_ref().filter(|_| event.id.is_some()) {
+            acks.record(&event);
+        }
+        Some(event.to_frame())
+    }
+}
+
+impl IntoResponse for SseStream {
+    fn into_response(self) -> Response {
+        let frames = stream::unfold(self, |mut stream| async move {
+            stream
+                .next_frame()
+                .await
+                .map(|frame| (Ok::<_, Infallible>(frame), stream))
+        });
+
+        (
+            [
+                (header::CONTENT_TYPE, "text/event-stream"),
+                (header::CACHE_CONTROL, "no-cache"),
+            ],
+            Body::from_stream(frames),
+        )
+            .into_response()
+    }
+}
+
+impl LocalResponse {
+    /// A streaming SSE response fed by a broadcast channel, for commands that
+    /// stream without going through a router. A lagging receiver gets a
+    /// comment and continues; see [`SseStream::from_broadcast`].
+    pub fn sse_from_broadcast(receiver: broadcast::Receiver<SseEvent>) -> StreamingResponse {
+        StreamingResponse::from_response(SseStream::from_broadcast(receiver).into_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads past the `: ok` and `retry:` frames every stream starts with.
+    async fn skip_preamble(stream: &mut SseStream) {
+        for _ in 0..2 {
+            stream.next_frame().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_first_frame_is_configured_retry() {
+        let config = SseConfig {
+            retry_ms: 5000,
+            keep_alive_interval: None,
+            open_comment: false,
+        };
+        let mut stream =
+            SseStream::new(stream::iter(vec![SseEvent::new("hello")])).with_config(config);
+
+        assert_eq!(
+            stream.next_frame().await.as_deref(),
+            Some("retry: 5000\n\n")
+        );
+        assert_eq!(
+            stream.next_frame().await.as_deref(),
+            Some("data: hello\n\n")
+        );
+        assert_eq!(stream.next_frame().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_open_comment_comes_before_events() {
+        let mut stream = SseStream::new(stream::iter(vec![SseEvent::new("hello")]))
+            .with_config(SseConfig::default());
+
+        assert_eq!(stream.next_frame().await.as_deref(), Some(": ok\n\n"));
+        assert_eq!(
+            stream.next_frame().await.as_deref(),
+            Some("retry: 3000\n\n")
+        );
+        assert_eq!(
+            stream.next_frame().await.as_deref(),
+            Some("data: hello\n\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_open_comment_is_sent_by_default() {
+        use crate::LocalRequest;
+        use axum::{routing::get, Router};
+        use std::collections::HashMap;
+
+        let mut router = Router::new().route(
+            "/events",
+            get(|| async { SseStream::new(stream::iter(vec![SseEvent::new("hello")])) }),
+        );
+        let request = LocalRequest {
+            uri: "/events".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let mut response = request.send_to_router_streaming(&mut router).await;
+        assert_eq!(response.next_chunk().await, Some(b": ok\n\n".to_vec()));
+        assert_eq!(
+            response.next_chunk().await,
+            Some(b"retry: 3000\n\n".to_vec())
+        );
+        assert_eq!(
+            response.next_chunk().await,
+            Some(b"data: hello\n\n".to_vec())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_keep_alive_is_sent_while_idle() {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut stream = SseStream::from_receiver(receiver).with_config(SseConfig {
+            retry_ms: 1000,
+            keep_alive_interval: Some(Duration::from_millis(20)),
+            open_comment: false,
+        });
+
+        stream.next_frame().await;
+        assert_eq!(stream.next_frame().await.as_deref(), Some(KEEP_ALIVE_FRAME));
+        sender.send(SseEvent::new("late")).unwrap();
+        assert_eq!(stream.next_frame().await.as_deref(), Some("data: late\n\n"));
+    }
+
+    #[tokio::test]
+    async fn test_acked_events_are_delivered() {
+        let events =
+            (1..=3).map(|id| SseEvent::new(format!("event {}", id)).with_id(id.to_string()));
+        let (mut stream, acks, tracker) =
+            SseStream::new(stream::iter(events.collect::<Vec<_>>())).with_acks();
+        while stream.next_frame().await.is_some() {}
+        assert_eq!(tracker.unacked_events().len(), 3);
+
+        assert!(acks.ack("2"));
+        assert_eq!(tracker.last_acked_id().as_deref(), Some("2"));
+        let unacked = tracker.unacked_events();
+        assert_eq!(unacked.len(), 1);
+        assert_eq!(unacked[0].id.as_deref(), Some("3"));
+    }
+
+    #[tokio::test]
+    async fn test_acks_are_processed_on_the_response_path() {
+        use crate::LocalRequest;
+        use axum::{routing::get, Router};
+        use std::collections::HashMap;
+
+        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        let stream = Arc::new(Mutex::new(Some(SseStream::from_receiver(event_receiver))));
+        let (handles_sender, mut handles_receiver) = mpsc::unbounded_channel();
+        let mut router = Router::new().route(
+            "/events",
+            get(move || async move {
+                let (stream, acks, tracker) = stream.lock().unwrap().take().unwrap().with_acks();
+                handles_sender.send((acks, tracker)).unwrap();
+                stream
+            }),
+        );
+        let request = LocalRequest {
+            uri: "/events".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let mut response = request.send_to_router_streaming(&mut router).await;
+        let (acks, tracker) = handles_receiver.recv().await.unwrap();
+        for _ in 0..2 {
+            response.next_chunk().await.unwrap();
+        }
+        for id in 1..=2 {
+            event_sender
+                .send(SseEvent::new("update").with_id(id.to_string()))
+                .unwrap();
+            response.next_chunk().await.unwrap();
+        }
+        assert!(acks.ack("1"));
+        event_sender
+            .send(SseEvent::new("update").with_id("3"))
+            .unwrap();
+        assert_eq!(
+            response.next_chunk().await,
+            Some(b"id: 3\ndata: update\n\n".to_vec())
+        );
+
+        let unacked: Vec<_> = tracker
+            .unacked_events()
+            .into_iter()
+            .filter_map(|event| event.id)
+            .collect();
+        assert_eq!(unacked, ["2", "3"]);
+        assert_eq!(tracker.last_acked_id().as_deref(), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn test_completion_trigger_is_last_frame() {
+        let mut stream = SseStream::new(stream::iter(vec![SseEvent::new("1"), SseEvent::new("2")]))
+            .with_completion_trigger("import-done");
+
+        let mut frames = Vec::new();
+        while let Some(frame) = stream.next_frame().await {
+            frames.push(frame);
+        }
+        assert_eq!(frames.len(), 5);
+        assert_eq!(
+            frames.last().map(String::as_str),
+            Some("event: hx-trigger\ndata: import-done\n\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_reaches_every_client() {
+        let (sender, first) = broadcast::channel(16);
+        let mut first = SseStream::from_broadcast(first);
+        let mut second = SseStream::from_broadcast(sender.subscribe());
+        skip_preamble(&mut first).await;
+        skip_preamble(&mut second).await;
+
+        sender.send(SseEvent::new("news")).unwrap();
+        assert_eq!(first.next_frame().await.as_deref(), Some("data: news\n\n"));
+        assert_eq!(second.next_frame().await.as_deref(), Some("data: news\n\n"));
+    }
+
+    #[tokio::test]
+    async fn test_lagged_receiver_gets_comment() {
+        let (sender, receiver) = broadcast::channel(1);
+        let mut stream = SseStream::from_broadcast(receiver);
+        sender.send(SseEvent::new("one")).unwrap();
+        sender.send(SseEvent::new("two")).unwrap();
+        skip_preamble(&mut stream).await;
+
+        assert_eq!(
+            stream.next_frame().await.as_deref(),
+            Some(": lagged, skipped 1 events\n\n")
+        );
+        assert_eq!(stream.next_frame().await.as_deref(), Some("data: two\n\n"));
+    }
+
+    #[tokio::test]
+    async fn test_lagged_broadcast_response_continues() {
+        let (sender, receiver) = broadcast::channel(2);
+        let mut response = LocalResponse::sse_from_broadcast(receiver);
+        for data in ["one", "two", "three", "four"] {
+            sender.send(SseEvent::new(data)).unwrap();
+        }
+        drop(sender);
+
+        assert_eq!(response.head().response_kind, crate::ResponseKind::Sse);
+        let mut chunks = Vec::new();
+        while let Some(chunk) = response.next_chunk().await {
+            chunks.push(String::from_utf8(chunk).unwrap());
+        }
+        assert_eq!(
+            chunks,
+            [
+                ": ok\n\n",
+                "retry: 3000\n\n",
+                ": lagged, skipped 2 events\n\n",
+                "data: three\n\n",
+                "data: four\n\n",
+            ]
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use crate::content_disposition::percent_decode;
+use crate::{LocalRequest, LocalResponse};
+use std::path::{Component, Path, PathBuf};
+
+/// Serves files from a directory before a request reaches the router, so
+/// bundled assets don't pay for routing and middleware.
+///
+/// Only `GET` and `HEAD` requests whose path starts with `prefix` are
+/// considered. The rest of the path is resolved under `root`; anything that
+/// would escape it (`..`) or doesn't name an existing file falls through to the
+/// router.
+#[derive(Clone, Debug)]
+pub struct StaticFileResolver {
+    pub prefix: String,
+    pub root: PathBuf,
+}
+
+impl StaticFileResolver {
+    pub fn new(prefix: impl Into<String>, root: impl Into<PathBuf>) -> Self {
+        let prefix = prefix.into();
+        StaticFileResolver {
+            prefix: format!("/{}", prefix.trim_matches('/')),
+            root: root.into(),
+        }
+    }
+
+    /// Returns the file for `request` as a response, or `None` if the router
+    /// should handle it.
+    pub async fn resolve(&self, request: &LocalRequest) -> Option<LocalResponse> {
+        let method = request.method.to_ascii_uppercase();
+        if method != "GET" && method != "HEAD" {
+            return None;
+        }
+        let path = self.file_path(request.path())?;
+        if !tokio::fs::metadata(&path).await.ok()?.is_file() {
+            return None;
+        }
+        let body = tokio::fs::read(&path).await.ok()?;
+
+        let mut response = LocalResponse::default()
+            .with_header("content-type", content_type_for(&path))
+            .with_header("content-length", body.len().to_string());
+        if method == "GET" {
+            response.body = body;
+        }
+        Some(response)
+    }
+
+    fn file_path(&self, request_path: &str) -> Option<PathBuf> {
+        let relative = if self.prefix == "/" {
+            request_path
+        } else {
+            request_path
+                .strip_prefix(&self.prefix)
+                .filter(|rest| rest.is_empty() || rest.starts_with('/'))?
+        };
+        let relative = String::from_utf8(percent_decode(relative)?).ok()?;
+
+        let mut path = self.root.clone();
+        for component in Path::new(relative.trim_start_matches('/')).components() {
+            match component {
+                Component::Normal(part) => path.push(part),
+                Component::CurDir => {}
+                _ => return None,
+            }
+        }
+        Some(path)
+    }
+}
+
+fn content_type_for(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "txt" => "text/plain; charset=utf-8",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "wasm" => "application/wasm",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dispatcher;
+    use axum::Router;
+    use std::collections::HashMap;
+
+    fn get(uri: &str) -> LocalRequest {
+        LocalRequest {
+            uri: uri.to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_static_file_bypasses_router() {
+        let root =
+            std::env::temp_dir().join(format!("tauri-axum-htmx-static-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("app.css"), "body { margin: 0 }").unwrap();
+
+        let dispatcher =
+            Dispatcher::new().with_static_files(StaticFileResolver::new("/assets", &root));
+        let mut router = Router::new();
+        let response = dispatcher
+            .send(get("/assets/app.css?v=2"), &mut router)
+            .await;
+        let missing = dispatcher
+            .send(get("/assets/missing.css"), &mut router)
+            .await;
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.body_str(), Some("body { margin: 0 }"));
+        assert_eq!(
+            response.header("content-type"),
+            Some("text/css; charset=utf-8")
+        );
+        assert_eq!(missing.status_code, 404);
+    }
+
+    #[test]
+    fn test_paths_outside_root_are_rejected() {
+        let resolver = StaticFileResolver::new("assets", "/srv/app");
+        assert_eq!(
+            resolver.file_path("/assets/img/a%20b.png"),
+            Some(PathBuf::from("/srv/app/img/a b.png"))
+        );
+        assert_eq!(resolver.file_path("/assets/../secret"), None);
+        assert_eq!(resolver.file_path("/assets/%2e%2e/secret"), None);
+        assert_eq!(resolver.file_path("/assetsfoo/x"), None);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{LocalRequest, LocalResponse};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+type RenderFragment = dyn Fn(&LocalRequest) -> String + Send + Sync;
+
+/// HTML fragments for error statuses, so `401`, `403`, `404` or `500` look the
+/// same across the whole router.
+///
+/// A fragment only replaces an empty body, so handlers that render their own
+/// error page keep it.
+#[derive(Clone, Default)]
+pub struct StatusHandlers {
+    handlers: HashMap<u16, Arc<RenderFragment>>,
+}
+
+impl fmt::Debug for StatusHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut statuses: Vec<_> = self.handlers.keys().collect();
+        statuses.sort();
+        f.debug_struct("StatusHandlers")
+            .field("statuses", &statuses)
+            .finish()
+    }
+}
+
+impl StatusHandlers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_fragment(self, status_code: u16, html: impl Into<String>) -> Self {
+        let html = html.into();
+        self.with_handler(status_code, move |_| html.clone())
+    }
+
+    /// Renders the fragment per request, e.g. to link back to the page that
+    /// was denied.
+    pub fn with_handler(
+        mut self,
+        status_code: u16,
+        render: impl Fn(&LocalRequest) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.handlers.insert(status_code, Arc::new(render));
+        self
+    }
+
+    pub fn apply(&self, request: &LocalRequest, response: LocalResponse) -> LocalResponse {
+        match self.handlers.get(&response.status_code) {
+            Some(render) if response.body.is_empty() => {
+                let body = render(request).into_bytes();
+                let content_length = body.len().to_string();
+                LocalResponse { body, ..response }
+                    .with_header("content-type", "text/html; charset=utf-8")
+                    .with_header("content-length", content_length)
+            }
+            _ => response,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use crate::Dispatcher;
+    use axum::http::StatusCode;
+    use axum::{routing::get, Router};
+
+    fn request(uri: &str) -> LocalRequest {
+        LocalRequest {
+            uri: uri.to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_forbidden_gets_fragment() {
+        let mut router = Router::new()
+            .route("/admin", get(|| async { StatusCode::FORBIDDEN }))
+            .route(
+                "/custom",
+                get(|| async { (StatusCode::FORBIDDEN, "<p>ask an admin</p>") }),
+            );
+        let dispatcher = Dispatcher::new().with_status_handlers(
+            StatusHandlers::new().with_fragment(403, "<div class=\"error\">Forbidden</div>"),
+        );
+
+        let response = dispatcher.send(request("/admin"), &mut router).await;
+        assert_eq!(response.status_code, 403);
+        assert_eq!(
+            response.body_str(),
+            Some("<div class=\"error\">Forbidden</div>")
+        );
+        assert_eq!(
+            response.header("content-type"),
+            Some("text/html; charset=utf-8")
+        );
+
+        let response = dispatcher.send(request("/custom"), &mut router).await;
+        assert_eq!(response.body_str(), Some("<p>ask an admin</p>"));
+    }
+}
@@ -0,0 +1,242 @@
+#[cfg(feature = "runtime")]
+use crate::LocalRequest;
+use crate::{fold_headers, LocalResponse, ResponseKind};
+use axum::body::BodyDataStream;
+use axum::response::Response;
+#[cfg(feature = "runtime")]
+use axum::Router;
+use futures_util::StreamExt;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+#[cfg(feature = "runtime")]
+use tower_service::Service;
+
+/// A response whose body is read chunk by chunk instead of being collected.
+///
+/// This is how long-lived bodies such as server-sent event streams cross the
+/// bridge: the Tauri command sends [`StreamingResponse::head`] to the webview and
+/// then forwards each chunk, for example over a `tauri::ipc::Channel`:
+///
+/// ```rust,ignore
+/// #[tauri::command]
+/// async fn local_app_stream(
+///     state: State<'_, TauriState>,
+///     local_request: LocalRequest,
+///     on_chunk: Channel<Vec<u8>>,
+/// ) -> Result<LocalResponse, ()> {
+///     let mut router = state.router.lock().await.clone();
+///     let mut response = local_request.send_to_router_streaming(&mut router).await;
+///     let head = response.head();
+///     tauri::async_runtime::spawn(async move {
+///         while let Some(chunk) = response.next_chunk().await {
+///             if on_chunk.send(chunk).is_err() {
+///                 break;
+///             }
+///         }
+///     });
+///     Ok(head)
+/// }
+/// ```
+pub struct StreamingResponse {
+    pub status_code: u16,
+    pub headers: BTreeMap<String, String>,
+    body: Option<BodyDataStream>,
+    pending: Option<Vec<u8>>,
+    /// Bytes read past the last line returned by [`StreamingResponse::next_line`].
+    partial_line: Vec<u8>,
+}
+
+impl StreamingResponse {
+    pub fn from_response(response: Response) -> Self {
+        let status_code = response.status().as_u16();
+        let headers = fold_headers(response.headers());
+
+        StreamingResponse {
+            status_code,
+            headers,
+            body: Some(response.into_body().into_data_stream()),
+            pending: None,
+            partial_line: Vec::new(),
+        }
+    }
+
+    /// The status and headers as a [`LocalResponse`] with an empty body.
+    pub fn head(&self) -> LocalResponse {
+        LocalResponse {
+            status_code: self.status_code,
+            headers: self.headers.clone(),
+            response_kind: ResponseKind::detect(&self.headers, true),
+            ..Default::default()
+        }
+    }
+
+    /// Waits for the next chunk of the body. Returns `None` once the body has
+    /// ended or failed.
+    pub async fn next_chunk(&mut self) -> Option<Vec<u8>> {
+        if let Some(chunk) = self.pending.take() {
+            return Some(chunk);
+        }
+        loop {
+            match self.body.as_mut()?.next().await {
+                Some(Ok(chunk)) if chunk.is_empty() => continue,
+                Some(Ok(chunk)) => return Some(chunk.to_vec()),
+                Some(Err(_)) | None => {
+                    self.body = None;
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Waits for the next non-empty line of the body, without its line ending.
+    /// A final line without a trailing newline is returned once the body ends.
+    ///
+    /// Don't mix with [`StreamingResponse::next_chunk`]: bytes buffered here
+    /// are not returned as chunks.
+    pub async fn next_line(&mut self) -> Option<Vec<u8>> {
+        loop {
+            if let Some(end) = self.partial_line.iter().position(|b| *b == b'\n') {
+                let mut line: Vec<u8> = self.partial_line.drain(..=end).collect();
+                line.pop();
+                if line.last() == Some(&b'\r') {
+                    line.pop();
+                }
+                if line.is_empty() {
+                    continue;
+                }
+                return Some(line);
+            }
+            match self.next_chunk().await {
+                Some(chunk) => self.partial_line.extend_from_slice(&chunk),
+                None if self.partial_line.is_empty() => return None,
+                None => return Some(std::mem::take(&mut self.partial_line)),
+            }
+        }
+    }
+
+    /// Reads the next line of an NDJSON body as a `T`.
+    pub async fn next_json<T: DeserializeOwned>(&mut self) -> Option<serde_json::Result<T>> {
+        let line = self.next_line().await?;
+        Some(serde_json::from_slice(&line))
+    }
+}
+
+impl From<LocalResponse> for StreamingResponse {
+    /// Wraps a buffered response, yielding its body as a single chunk.
+    fn from(response: LocalResponse) -> Self {
+        StreamingResponse {
+            status_code: response.status_code,
+            headers: response.headers,
+            body: None,
+            pending: Some(response.body).filter(|body| !body.is_empty()),
+            partial_line: Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "runtime")]
+impl LocalRequest {
+    /// Sends the request like [`LocalRequest::send_to_router`] but returns as soon
+    /// as the response head is available, leaving the body to be streamed.
+    pub async fn send_to_router_streaming(self, router: &mut Router) -> StreamingResponse {
+        match self.to_axum_request() {
+            Ok(request) => match router.call(request).await {
+                Ok(response) => StreamingResponse::from_response(response),
+                Err(error) => LocalResponse::internal_server_error(error).into(),
+            },
+            Err(error) => LocalResponse::request_error(error).into(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get};
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_body_is_read_in_chunks() {
+        let mut router = Router::new().route(
+            "/chunks",
+            get(|| async {
+                let chunks = futures_util::stream::iter(vec![
+                    Ok::<_, std::convert::Infallible>("first"),
+                    Ok("second"),
+                ]);
+                Body::from_stream(chunks)
+            }),
+        );
+        let request = LocalRequest {
+            uri: "/chunks".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let mut response = request.send_to_router_streaming(&mut router).await;
+        assert_eq!(response.head().status_code, 200);
+        assert_eq!(response.next_chunk().await, Some(b"first".to_vec()));
+        assert_eq!(response.next_chunk().await, Some(b"second".to_vec()));
+        assert_eq!(response.next_chunk().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_lines_arrive_as_separate_objects() {
+        let mut router = Router::new().route(
+            "/feed",
+            get(|| async {
+                // Lines split across chunks on purpose.
+                let chunks: Vec<Result<&str, std::convert::Infallible>> =
+                    vec![Ok("{\"n\":1}\n{\"n\""), Ok(":2}\n"), Ok("{\"n\":3}\n")];
+                (
+                    [("content-type", "application/x-ndjson")],
+                    Body::from_stream(futures_util::stream::iter(chunks)),
+                )
+            }),
+        );
+        let request = LocalRequest {
+            uri: "/feed".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let mut response = request.send_to_router_streaming(&mut router).await;
+        assert_eq!(response.head().response_kind, ResponseKind::NdjsonStream);
+        let mut values = Vec::new();
+        while let Some(value) = response.next_json::<serde_json::Value>().await {
+            values.push(value.unwrap()["n"].as_u64().unwrap());
+        }
+        assert_eq!(values, [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_headers_are_folded_like_buffered_responses() {
+        use axum::response::AppendHeaders;
+
+        let mut router = Router::new().route(
+            "/events",
+            get(|| async {
+                (
+                    AppendHeaders([
+                        ("set-cookie", "a=1"),
+                        ("set-cookie", "b=2"),
+                        ("connection", "keep-alive"),
+                    ]),
+                    "data: hi\n\n",
+                )
+            }),
+        );
+        let request = LocalRequest {
+            uri: "/events".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+
+        let head = request.send_to_router_streaming(&mut router).await.head();
+        assert_eq!(head.header("set-cookie"), Some("a=1\nb=2"));
+        assert_eq!(head.header("connection"), None);
+    }
+}
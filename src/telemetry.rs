@@ -0,0 +1,111 @@
+use axum::body::Body;
+use axum::http::Request;
+use tracing::Span;
+
+/// Creates the span every request sent to a router runs in.
+///
+/// With the `otel` feature the fields follow the OpenTelemetry HTTP semantic
+/// conventions (`http.method`, `http.route`, `http.status_code`), so exporters
+/// such as `tracing-opentelemetry` pick them up without extra mapping.
+/// `http.route` is the request path, as the matched route template is not
+/// visible outside the router.
+pub(crate) fn request_span(request: &Request<Body>) -> Span {
+    #[cfg(feature = "otel")]
+    let span = tracing::debug_span!(
+        "local_request",
+        otel.kind = "server",
+        http.method = %request.method(),
+        http.route = %request.uri().path(),
+        http.status_code = tracing::field::Empty,
+    );
+    #[cfg(not(feature = "otel"))]
+    let span = tracing::debug_span!(
+        "local_request",
+        method = %request.method(),
+        uri = %request.uri(),
+        status = tracing::field::Empty,
+    );
+    span
+}
+
+pub(crate) fn record_status(span: &Span, status_code: u16) {
+    #[cfg(feature = "otel")]
+    span.record("http.status_code", status_code);
+    #[cfg(not(feature = "otel"))]
+    span.record("status", status_code);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LocalRequest;
+    use axum::{routing::get, Router};
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Collects the fields recorded on every span.
+    #[derive(Clone, Default)]
+    struct FieldRecorder {
+        fields: Arc<Mutex<HashMap<String, String>>>,
+    }
+
+    impl Visit for FieldRecorder {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.fields
+                .lock()
+                .unwrap()
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    impl Subscriber for FieldRecorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            span.record(&mut self.clone());
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, values: &Record<'_>) {
+            values.record(&mut self.clone());
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[tokio::test]
+    async fn test_span_records_method_and_status() {
+        let recorder = FieldRecorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let mut router = Router::new().route("/traced", get(|| async { "ok" }));
+        let request = LocalRequest {
+            uri: "/traced?page=1".to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        };
+        request.send_to_router(&mut router).await;
+
+        let fields = recorder.fields.lock().unwrap();
+        if cfg!(feature = "otel") {
+            assert_eq!(fields.get("http.method").unwrap(), "GET");
+            assert_eq!(fields.get("http.route").unwrap(), "/traced");
+            assert_eq!(fields.get("http.status_code").unwrap(), "200");
+        } else {
+            assert_eq!(fields.get("method").unwrap(), "GET");
+            assert_eq!(fields.get("status").unwrap(), "200");
+        }
+    }
+}
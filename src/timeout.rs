@@ -0,0 +1,128 @@
+#[cfg(feature = "runtime")]
+use crate::{call_router, LocalRequest, LocalResponse};
+#[cfg(feature = "runtime")]
+use axum::Router;
+#[cfg(feature = "runtime")]
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// The point in time by which a request sent with
+/// [`LocalRequest::send_to_router_with_timeout`] must complete.
+///
+/// It is inserted into the request extensions so handlers can bound their own
+/// work, e.g. with `Extension(deadline): Extension<Deadline>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Deadline(pub Instant);
+
+impl Deadline {
+    /// The time left before the deadline, or zero if it has passed.
+    pub fn remaining(&self) -> Duration {
+        self.0.saturating_duration_since(Instant::now())
+    }
+}
+
+/// When the request was handed to the router. Every send path inserts it into
+/// the request extensions, so handlers can work out how much of a latency
+/// budget is left with `Extension(start): Extension<RequestStart>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RequestStart(pub Instant);
+
+impl RequestStart {
+    pub fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+#[cfg(feature = "runtime")]
+impl LocalRequest {
+    /// Sends the request like [`LocalRequest::send_to_router`], answering with a
+    /// `504 Gateway Timeout` if the router does not respond within `timeout`.
+    pub async fn send_to_router_with_timeout(
+        self,
+        router: &mut Router,
+        timeout: Duration,
+    ) -> LocalResponse {
+        let mut request = match self.to_axum_request() {
+            Ok(request) => request,
+            Err(error) => return LocalResponse::request_error(error),
+        };
+        request
+            .extensions_mut()
+            .insert(Deadline(Instant::now() + timeout));
+
+        match tokio::time::timeout(timeout, call_router(router, request)).await {
+            Ok(response) => response,
+            Err(_) => LocalResponse {
+                status_code: 504,
+                body: format!("Request timed out after {}ms", timeout.as_millis()).into(),
+                headers: BTreeMap::new(),
+                generated_by_crate: true,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Extension};
+    use std::collections::HashMap;
+
+    fn request(uri: &str) -> LocalRequest {
+        LocalRequest {
+            uri: uri.to_string(),
+            method: "GET".to_string(),
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_start_is_available_to_handlers() {
+        let mut router = Router::new().route(
+            "/start",
+            get(|Extension(start): Extension<RequestStart>| async move {
+                start.elapsed().as_millis().to_string()
+            }),
+        );
+
+        let response = request("/start").send_to_router(&mut router).await;
+        assert_eq!(response.status_code, 200);
+        let elapsed: u128 = response.body_str().unwrap().parse().unwrap();
+        assert!(elapsed < 1_000);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_is_available_to_handlers() {
+        let mut router = Router::new().route(
+            "/deadline",
+            get(|Extension(deadline): Extension<Deadline>| async move {
+                deadline.remaining().as_millis().to_string()
+            }),
+        );
+
+        let response = request("/deadline")
+            .send_to_router_with_timeout(&mut router, Duration::from_secs(5))
+            .await;
+        assert_eq!(response.status_code, 200);
+        let remaining: u128 = response.body_str().unwrap().parse().unwrap();
+        assert!(remaining > 4_000 && remaining <= 5_000);
+    }
+
+    #[tokio::test]
+    async fn test_slow_handler_times_out() {
+        let mut router = Router::new().route(
+            "/slow",
+            get(|| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                "too late"
+            }),
+        );
+
+        let response = request("/slow")
+            .send_to_router_with_timeout(&mut router, Duration::from_millis(10))
+            .await;
+        assert_eq!(response.status_code, 504);
+    }
+}
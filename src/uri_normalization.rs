@@ -0,0 +1,114 @@
+/// What to do with a trailing `/` on the request path. The root path `/` is
+/// never changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrailingSlash {
+    #[default]
+    Keep,
+    /// `/items/` becomes `/items`.
+    Strip,
+    /// `/items` becomes `/items/`.
+    Add,
+}
+
+/// Rewrites request paths before routing so equivalent URIs reach the same
+/// route. The query string and fragment are left untouched.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UriNormalization {
+    /// Resolves `.` and `..` segments, as in RFC 3986 section 5.2.4.
+    pub remove_dot_segments: bool,
+    pub trailing_slash: TrailingSlash,
+}
+
+impl UriNormalization {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_dot_segments_removed(mut self) -> Self {
+        self.remove_dot_segments = true;
+        self
+    }
+
+    pub fn with_trailing_slash(mut self, trailing_slash: TrailingSlash) -> Self {
+        self.trailing_slash = trailing_slash;
+        self
+    }
+
+    pub fn normalize(&self, uri: &str) -> String {
+        let end = uri.find(['?', '#']).unwrap_or(uri.len());
+        let (path, rest) = uri.split_at(end);
+
+        let mut path = if self.remove_dot_segments {
+            remove_dot_segments(path)
+        } else {
+            path.to_string()
+        };
+        if path.len() > 1 {
+            match self.trailing_slash {
+                TrailingSlash::Keep => {}
+                TrailingSlash::Strip => {
+                    let trimmed = path.trim_end_matches('/');
+                    path = if trimmed.is_empty() {
+                        "/".to_string()
+                    } else {
+                        trimmed.to_string()
+                    };
+                }
+                TrailingSlash::Add => {
+                    if !path.ends_with('/') {
+                        path.push('/');
+                    }
+                }
+            }
+        }
+        path + rest
+    }
+}
+
+fn remove_dot_segments(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    let mut parts = path.split('/').skip(1).peekable();
+    while let Some(segment) = parts.next() {
+        let is_last = parts.peek().is_none();
+        match segment {
+            "." => {
+                if is_last {
+                    segments.push("");
+                }
+            }
+            ".." => {
+                segments.pop();
+                if is_last {
+                    segments.push("");
+                }
+            }
+            segment => segments.push(segment),
+        }
+    }
+    format!("/{}", segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dot_segments_are_removed() {
+        let normalization = UriNormalization::new().with_dot_segments_removed();
+        assert_eq!(normalization.normalize("/a/./b"), "/a/b");
+        assert_eq!(normalization.normalize("/a/b/../c?x=../y"), "/a/c?x=../y");
+        assert_eq!(normalization.normalize("/../a/."), "/a/");
+        assert_eq!(UriNormalization::new().normalize("/a/./b"), "/a/./b");
+    }
+
+    #[test]
+    fn test_trailing_slash_modes() {
+        let strip = UriNormalization::new().with_trailing_slash(TrailingSlash::Strip);
+        assert_eq!(strip.normalize("/items/?page=2"), "/items?page=2");
+        assert_eq!(strip.normalize("/"), "/");
+
+        let add = UriNormalization::new().with_trailing_slash(TrailingSlash::Add);
+        assert_eq!(add.normalize("/items#top"), "/items/#top");
+        assert_eq!(add.normalize("/items/"), "/items/");
+    }
+}